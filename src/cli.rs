@@ -0,0 +1,260 @@
+use std::path::{Path, PathBuf};
+
+use raytracing_challenge::raytracer::{camera::SamplePattern, exporter::BitDepth};
+
+pub const USAGE: &str = "\
+Usage: raytracing-challenge [OPTIONS] [SCENE]
+
+Renders the scene file SCENE (YAML), read from standard input if it is '-',
+or a built-in demo scene if none is given.
+
+Options:
+  -o, --output <PATH>    Output image, the format is inferred from the extension (.ppm, .png) [default: out.ppm]
+      --width <PIXELS>   Overrides the width of the scene's camera
+      --height <PIXELS>  Overrides the height of the scene's camera
+      --bit-depth <BITS> Bits per color channel of the output image, 8 or 16 [default: 8]
+      --binary           Writes binary (P6) instead of plain text (P3) PPM images
+  -t, --threads <N>      Number of render threads [default: number of CPUs]
+  -s, --samples <N>      Rays averaged per pixel to smooth jagged edges [default: 1]
+      --sample-pattern <PATTERN>
+                         Where in the pixel the samples are taken: grid, jittered or random [default: grid]
+  -q, --quiet            Only print errors
+      --progress         Print the progress while rendering
+  -h, --help             Print this help
+";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Ppm,
+    Png,
+}
+
+impl OutputFormat {
+    pub fn from_path(path: &Path) -> Result<OutputFormat, String> {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+            Some("ppm") => Ok(OutputFormat::Ppm),
+            Some("png") => Ok(OutputFormat::Png),
+            Some(other) => Err(format!("unsupported output format '.{}'", other)),
+            None => Err(format!("can't infer the output format of '{}' without an extension", path.display())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Progress,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub scene: Option<PathBuf>,
+    pub output: PathBuf,
+    pub format: OutputFormat,
+    pub bit_depth: BitDepth,
+    pub binary: bool,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub threads: Option<usize>,
+    pub samples: usize,
+    pub sample_pattern: SamplePattern,
+    pub verbosity: Verbosity,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Help,
+    Render(Options),
+}
+
+/// Parses the command line arguments, without the program name.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut scene = None;
+    let mut output = PathBuf::from("out.ppm");
+    let mut bit_depth = BitDepth::Eight;
+    let mut binary = false;
+    let mut width = None;
+    let mut height = None;
+    let mut threads = None;
+    let mut samples = 1;
+    let mut sample_pattern = SamplePattern::Grid;
+    let mut verbosity = Verbosity::Normal;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // both `--option value` and `--option=value` are accepted
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || inline_value.clone().or_else(|| args.next()).ok_or_else(|| format!("missing value for '{}'", name));
+        let flag = || match &inline_value {
+            Some(value) => Err(format!("'{}' doesn't take a value, got '{}'", name, value)),
+            None => Ok(()),
+        };
+
+        match name.as_str() {
+            "-h" | "--help" => return flag().map(|_| Command::Help),
+            "-o" | "--output" => output = PathBuf::from(value()?),
+            "--width" => width = Some(positive(&name, &value()?)?),
+            "--height" => height = Some(positive(&name, &value()?)?),
+            "--bit-depth" => bit_depth = match value()?.as_str() {
+                "8" => BitDepth::Eight,
+                "16" => BitDepth::Sixteen,
+                other => return Err(format!("'--bit-depth' expects 8 or 16, got '{}'", other)),
+            },
+            "--binary" => binary = flag().map(|_| true)?,
+            "-t" | "--threads" => threads = Some(positive(&name, &value()?)?),
+            "-s" | "--samples" => samples = positive(&name, &value()?)?,
+            "--sample-pattern" => sample_pattern = match value()?.as_str() {
+                "grid" => SamplePattern::Grid,
+                "jittered" => SamplePattern::Jittered,
+                "random" => SamplePattern::Random,
+                other => return Err(format!("'--sample-pattern' expects grid, jittered or random, got '{}'", other)),
+            },
+            "-q" | "--quiet" => verbosity = flag().map(|_| Verbosity::Quiet)?,
+            "--progress" => verbosity = flag().map(|_| Verbosity::Progress)?,
+            _ if name.starts_with('-') && name != "-" => return Err(format!("unknown option '{}'", name)),
+            _ if scene.is_none() => scene = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument '{}', only one scene can be rendered", arg)),
+        }
+    }
+
+    let format = OutputFormat::from_path(&output)?;
+    if binary && format != OutputFormat::Ppm {
+        return Err("'--binary' only applies to PPM images".to_string());
+    }
+    Ok(Command::Render(Options { scene, output, format, bit_depth, binary, width, height, threads, samples, sample_pattern, verbosity }))
+}
+
+fn positive(name: &str, value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("'{}' expects a positive whole number, got '{}'", name, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn parse_args_defaults() {
+        // When
+        let command = parse(&[]).unwrap();
+
+        // Then
+        assert_eq!(command, Command::Render(Options {
+            scene: None,
+            output: PathBuf::from("out.ppm"),
+            format: OutputFormat::Ppm,
+            bit_depth: BitDepth::Eight,
+            binary: false,
+            width: None,
+            height: None,
+            threads: None,
+            samples: 1,
+            sample_pattern: SamplePattern::Grid,
+            verbosity: Verbosity::Normal,
+        }));
+    }
+
+    #[test]
+    fn parse_args_reads_all_options() {
+        // When
+        let command = parse(&["scene.yml", "-o", "image.PNG", "--width=320", "--height", "240", "--bit-depth", "16", "-t", "3", "--samples", "4", "--sample-pattern=jittered", "--progress"]).unwrap();
+
+        // Then
+        assert_eq!(command, Command::Render(Options {
+            scene: Some(PathBuf::from("scene.yml")),
+            output: PathBuf::from("image.PNG"),
+            format: OutputFormat::Png,
+            bit_depth: BitDepth::Sixteen,
+            binary: false,
+            width: Some(320),
+            height: Some(240),
+            threads: Some(3),
+            samples: 4,
+            sample_pattern: SamplePattern::Jittered,
+            verbosity: Verbosity::Progress,
+        }));
+    }
+
+    #[test]
+    fn parse_args_reads_binary_ppm() {
+        // When
+        let command = parse(&["--binary", "-o", "image.ppm"]).unwrap();
+
+        // Then
+        match command {
+            Command::Render(options) => assert!(options.binary && options.format == OutputFormat::Ppm),
+            Command::Help => panic!("expected render options"),
+        }
+    }
+
+    #[test]
+    fn parse_args_ignores_arguments_after_help() {
+        // When & Then
+        assert_eq!(parse(&["scene.yml", "-h", "--bogus"]), Ok(Command::Help));
+    }
+
+    #[test]
+    fn parse_args_rejects_invalid_arguments_before_help() {
+        // When & Then
+        assert_eq!(parse(&["scene.yml", "--bogus", "-h"]), Err("unknown option '--bogus'".to_string()));
+    }
+
+    #[test]
+    fn parse_args_reads_scene_from_stdin() {
+        // When
+        let command = parse(&["-"]).unwrap();
+
+        // Then
+        match command {
+            Command::Render(options) => assert_eq!(options.scene, Some(PathBuf::from("-"))),
+            Command::Help => panic!("expected render options"),
+        }
+    }
+
+    #[test]
+    fn parse_args_rejects_values_for_flags() {
+        // Given
+        let cases = ["--binary=yes", "--quiet=1", "--progress=x", "--help=foo"];
+
+        for arg in cases {
+            // When
+            let result = parse(&[arg]);
+
+            // Then
+            let (name, value) = arg.split_once('=').unwrap();
+            assert_eq!(result, Err(format!("'{}' doesn't take a value, got '{}'", name, value)));
+        }
+    }
+
+    #[test]
+    fn parse_args_rejects_invalid_arguments() {
+        // Given
+        let cases: [&[&str]; 10] = [
+            &["--width"],
+            &["--sample-pattern", "poisson"],
+            &["--bit-depth", "12"],
+            &["--binary", "-o", "image.png"],
+            &["--width", "0"],
+            &["--threads", "many"],
+            &["-o", "image.jpg"],
+            &["-o", "image"],
+            &["a.yml", "b.yml"],
+            &["--colour"],
+        ];
+
+        for args in cases {
+            // When & Then
+            assert!(parse(args).is_err(), "{:?}", args);
+        }
+    }
+}
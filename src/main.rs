@@ -1,25 +1,112 @@
-use std::{fs::File, io::BufWriter};
+mod cli;
 
-use raytracer::{objects::{intersection::Intersection, lights::PointLight, materials::Material, object::Object, sphere::Sphere}, ray::Ray, transformation, tuple::Tuple};
+use std::{f64::consts::PI, fs::File, io::{BufWriter, Read, Write}, path::Path, process::ExitCode, time::Instant};
 
-use crate::raytracer::{canvas::Canvas, color::Color, exporter::{PPMExporter, Exporter}};
+use cli::{Command, Options, OutputFormat, Verbosity, USAGE};
+use raytracing_challenge::raytracer::{camera::Camera, canvas::Canvas, color::Color, exporter::{BitDepth, Exporter, PNGExporter, PPMEncoding, PPMExporter}, objects::{lights::PointLight, materials::Material, object::Object, patterns::CheckersPattern, plane::Plane, sphere::Sphere}, scene::loader::{load_scene, parse_scene}, transformation, tuple::Tuple, world::World};
 
-mod raytracer;
+/// Groups with more objects than this are split into a bounding volume hierarchy before rendering.
+const BVH_THRESHOLD: usize = 8;
 
-fn main() {
+fn main() -> ExitCode {
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Render(options)) => options,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let (camera, mut world) = match &options.scene {
+        Some(path) if path.as_os_str() == "-" => {
+            let mut source = String::new();
+            std::io::stdin().read_to_string(&mut source).map_err(|err| format!("can't read the scene from standard input: {}", err))?;
+            // relative paths in the scene, e.g. to OBJ files, are resolved against the working directory
+            parse_scene(&source, Path::new(""))
+                .map_err(|err| format!("<stdin>: {}", err))?
+                .into_parts()
+        }
+        Some(path) => load_scene(path)
+            .map_err(|err| format!("{}: {}", path.display(), err))?
+            .into_parts(),
+        None => demo_scene(),
+    };
+    world.divide(BVH_THRESHOLD);
+
+    let mut camera = resize_camera(camera, options.width, options.height);
+    camera.set_threads(options.threads.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get())));
+    camera.set_samples(options.samples);
+    camera.set_sample_pattern(options.sample_pattern);
 
-    use std::time::Instant;
     let now = Instant::now();
+    let canvas = if options.verbosity == Verbosity::Progress {
+        let rows = camera.vsize();
+        let canvas = camera.render_with_progress(&world, &|finished| {
+            eprint!("\rRendering: {}/{} rows", finished, rows);
+        });
+        eprintln!();
+        canvas
+    } else {
+        camera.render(&world)
+    };
+    if options.verbosity != Verbosity::Quiet {
+        println!("Rendering took: {:.2?}", now.elapsed());
+    }
+
+    let now = Instant::now();
+    export(&canvas, options)?;
+    if options.verbosity != Verbosity::Quiet {
+        println!("Exporting took: {:.2?}", now.elapsed());
+    }
+    Ok(())
+}
 
-    let ray_origin = Tuple::point(0.0, 0.0, -5.0);
-    let wall_z = 10.0;
-    let wall_size = 7.0;
-    let canvas_pixel = 200;
-    let pixel_size = wall_size / canvas_pixel as f64;
-    let half = wall_size / 2.0;
+// Creates a camera with the given size, keeping the field of view and transform of the original.
+fn resize_camera(camera: Camera, width: Option<usize>, height: Option<usize>) -> Camera {
+    if width.is_none() && height.is_none() {
+        return camera;
+    }
 
-    let mut canvas = Canvas::new(canvas_pixel, canvas_pixel);
+    let mut resized = Camera::new(width.unwrap_or(camera.hsize()), height.unwrap_or(camera.vsize()), camera.field_of_view());
+    resized.set_transform(camera.transform().clone());
+    resized
+}
 
+fn export(canvas: &Canvas, options: &Options) -> Result<(), String> {
+    let exporter: Box<dyn Exporter> = match options.format {
+        OutputFormat::Ppm => {
+            let encoding = if options.binary { PPMEncoding::Binary } else { PPMEncoding::Ascii };
+            let max_value = match options.bit_depth {
+                BitDepth::Eight => u8::MAX as u16,
+                BitDepth::Sixteen => u16::MAX,
+            };
+            Box::new(PPMExporter::with_options(encoding, max_value))
+        }
+        OutputFormat::Png => Box::new(PNGExporter::with_bit_depth(options.bit_depth)),
+    };
+
+    let error = |err: std::io::Error| format!("failed to write '{}': {}", options.output.display(), err);
+    let file = File::create(&options.output).map_err(error)?;
+    let mut writer = BufWriter::new(file);
+    exporter.export(canvas, &mut writer).map_err(error)?;
+    writer.flush().map_err(error)
+}
+
+fn demo_scene() -> (Camera, World) {
     let mut material = Material::default();
     material.set_color(Color::new(1.0, 0.2, 1.0));
 
@@ -30,45 +117,22 @@ fn main() {
     let light_color = Color::new(1.0, 1.0, 1.0);
     let light = PointLight::new(light_position, light_color);
 
-    for y in 0..canvas.height() {
-        let world_y = half - pixel_size * y as f64;
+    let mut floor = Plane::default();
+    floor.set_transform(transformation::translation(0.0, -1.0, 0.0));
+    let mut floor_material = Material::default();
+    floor_material.set_pattern(Box::new(CheckersPattern::new(Color::new(0.9, 0.9, 0.9), Color::new(0.1, 0.1, 0.1))));
+    floor_material.set_specular(0.0);
+    floor_material.set_reflective(0.2);
+    floor.set_material(floor_material);
 
-        for x in 0..canvas.width() {
-            let world_x = -half + pixel_size * x as f64;
+    let world = World::new(vec![Box::new(floor), Box::new(s)], vec![Box::new(light)]);
 
-            let position = Tuple::point(world_x, world_y, wall_z);
-
-            let r = Ray::new(ray_origin.clone(), (position - &ray_origin).normalize());
-            let xs = s.intersects(&r);
-            
-            let hit = Intersection::from_hit(&xs);
-
-            if hit.is_some() {
-                let point = r.position(hit.unwrap().t());
-                let normal = hit.unwrap().object().normal_at(&point);
-                let eye = -r.direction();
-                let color = hit.unwrap().object().material().lighting(&light, &point, &eye, &normal);
-                canvas.write_pixel(x, y, color);
-            }
-        }
-    }
-
-    let elapsed = now.elapsed();
-    println!("Rendering took: {:.2?}", elapsed);
-
-    export_ppm(&canvas);
-}
-
-fn export_ppm(c: &Canvas) {
-    let exporter = PPMExporter::new();
-    let file= File::create("out.ppm").unwrap();
-    let mut writer = BufWriter::new(file);
-
-    use std::time::Instant;
-    let now = Instant::now();
-    
-    exporter.export(&c, &mut writer).unwrap();
+    let mut camera = Camera::new(200, 200, PI / 3.0);
+    camera.set_transform(transformation::view_transform(
+        &Tuple::point(0.0, 1.5, -5.0),
+        &Tuple::point(0.0, 0.0, 0.0),
+        &Tuple::vector(0.0, 1.0, 0.0),
+    ));
 
-    let elapsed = now.elapsed();
-    println!("Exporting took: {:.2?}", elapsed);
+    (camera, world)
 }
@@ -0,0 +1,584 @@
+use std::{sync::atomic::{AtomicUsize, Ordering}, thread};
+
+use super::{canvas::Canvas, color::Color, matrix::Matrix, random::Random, ray::Ray, tuple::Tuple, world::World};
+
+/// Where in a pixel the rays of a supersampled pixel go through.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SamplePattern {
+    /// The centers of the cells of a near-square grid covering the pixel.
+    Grid,
+    /// A random spot in each cell of the grid.
+    Jittered,
+    /// Random spots anywhere in the pixel.
+    Random,
+}
+
+pub struct Camera {
+    hsize: usize,
+    vsize: usize,
+    field_of_view: f64,
+    transform: Matrix,
+    transform_inverse: Matrix,
+    half_width: f64,
+    half_height: f64,
+    pixel_size: f64,
+    threads: usize,
+    samples: usize,
+    sample_pattern: SamplePattern,
+}
+
+impl Camera {
+    pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Camera {
+        // the canvas is one unit in front of the camera, so half of it spans tan(fov / 2)
+        let half_view = (field_of_view / 2.0).tan();
+        let aspect = hsize as f64 / vsize as f64;
+
+        let (half_width, half_height) = if aspect >= 1.0 {
+            (half_view, half_view / aspect)
+        } else {
+            (half_view * aspect, half_view)
+        };
+        let pixel_size = (half_width * 2.0) / hsize as f64;
+
+        Self {
+            hsize,
+            vsize,
+            field_of_view,
+            transform: Matrix::identity(4, 4),
+            transform_inverse: Matrix::identity(4, 4),
+            half_width,
+            half_height,
+            pixel_size,
+            threads: 1,
+            samples: 1,
+            sample_pattern: SamplePattern::Grid,
+        }
+    }
+
+    pub fn hsize(&self) -> usize {
+        self.hsize
+    }
+
+    pub fn vsize(&self) -> usize {
+        self.vsize
+    }
+
+    pub fn field_of_view(&self) -> f64 {
+        self.field_of_view
+    }
+
+    pub fn transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    pub fn set_transform(&mut self, transform: Matrix) {
+        self.transform_inverse = transform.inverse();
+        self.transform = transform;
+    }
+
+    pub fn pixel_size(&self) -> f64 {
+        self.pixel_size
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Sets how many threads `render` distributes the rows of the image over, at least one is always used.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Sets how many rays are averaged for every pixel, at least one is always used.
+    pub fn set_samples(&mut self, samples: usize) {
+        self.samples = samples.max(1);
+    }
+
+    pub fn sample_pattern(&self) -> SamplePattern {
+        self.sample_pattern
+    }
+
+    pub fn set_sample_pattern(&mut self, sample_pattern: SamplePattern) {
+        self.sample_pattern = sample_pattern;
+    }
+
+    /// The spots in the pixel at `x` and `y` its samples are taken at, as offsets from its top left corner between 0 and 1.
+    /// Random offsets are seeded from the pixel and the sample index, so every render of a pixel uses the same ones.
+    pub fn sample_offsets(&self, x: usize, y: usize) -> Vec<(f64, f64)> {
+        let jitter = |i: usize| {
+            let mut rng = Random::new(&[x as u64, y as u64, i as u64]);
+            (rng.next_f64(), rng.next_f64())
+        };
+        if self.sample_pattern == SamplePattern::Random {
+            return (0..self.samples).map(jitter).collect();
+        }
+
+        // split the pixel into ceil(sqrt(samples)) rows and spread the samples over them, so counts
+        // that aren't squares still cover the pixel in both directions; the first rows take the remainder
+        let samples = self.samples;
+        let rows = (1..=samples).find(|r| r * r >= samples).unwrap_or(1);
+        let columns_in = move |row: usize| samples / rows + usize::from(row < samples % rows);
+        (0..rows)
+            .flat_map(|row| (0..columns_in(row)).map(move |column| (column, row)))
+            .enumerate()
+            .map(|(i, (column, row))| {
+                let (dx, dy) = match self.sample_pattern {
+                    SamplePattern::Jittered => jitter(i),
+                    _ => (0.5, 0.5),
+                };
+                ((column as f64 + dx) / columns_in(row) as f64, (row as f64 + dy) / rows as f64)
+            })
+            .collect()
+    }
+
+    pub fn ray_for_pixel(&self, x: usize, y: usize) -> Ray {
+        self.ray_for_pixel_offset(x, y, 0.5, 0.5)
+    }
+
+    /// The ray through the point `dx` and `dy` (between 0 and 1) away from the top left corner of a pixel.
+    pub fn ray_for_pixel_offset(&self, x: usize, y: usize, dx: f64, dy: f64) -> Ray {
+        // offset from the edge of the canvas to the point in the pixel
+        let xoffset = (x as f64 + dx) * self.pixel_size;
+        let yoffset = (y as f64 + dy) * self.pixel_size;
+
+        // the untransformed coordinates of the pixel in world space
+        // (the camera looks toward -z, so +x is to the left)
+        let world_x = self.half_width - xoffset;
+        let world_y = self.half_height - yoffset;
+
+        let pixel = &self.transform_inverse * &Tuple::point(world_x, world_y, -1.0);
+        let origin = &self.transform_inverse * &Tuple::point(0.0, 0.0, 0.0);
+        let direction = (pixel - &origin).normalize();
+
+        Ray::new(origin, direction)
+    }
+
+    pub fn render(&self, world: &World) -> Canvas {
+        self.render_with_progress(world, &|_| {})
+    }
+
+    /// Like `render`, but calls `progress` with the number of finished rows every time a row is done.
+    pub fn render_with_progress(&self, world: &World, progress: &(dyn Fn(usize) + Sync)) -> Canvas {
+        let mut image = Canvas::new(self.hsize, self.vsize);
+
+        if self.threads == 1 {
+            for y in 0..self.vsize {
+                self.write_row(&mut image, y, self.render_row(world, y));
+                progress(y + 1);
+            }
+            return image;
+        }
+
+        // the threads take the next row that hasn't been rendered yet until none are left,
+        // which keeps them busy even if some parts of the image are more expensive than others
+        let next_row = AtomicUsize::new(0);
+        let finished_rows = AtomicUsize::new(0);
+        let rows: Vec<(usize, Vec<Color>)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads)
+                .map(|_| scope.spawn(|| {
+                    let mut rows = vec![];
+                    loop {
+                        let y = next_row.fetch_add(1, Ordering::Relaxed);
+                        if y >= self.vsize {
+                            return rows;
+                        }
+                        rows.push((y, self.render_row(world, y)));
+                        progress(finished_rows.fetch_add(1, Ordering::Relaxed) + 1);
+                    }
+                }))
+                .collect();
+            workers.into_iter().flat_map(|w| w.join().unwrap()).collect()
+        });
+
+        for (y, row) in rows {
+            self.write_row(&mut image, y, row);
+        }
+        image
+    }
+
+    fn render_row(&self, world: &World, y: usize) -> Vec<Color> {
+        (0..self.hsize).map(|x| self.render_pixel(world, x, y)).collect()
+    }
+
+    fn render_pixel(&self, world: &World, x: usize, y: usize) -> Color {
+        if self.samples == 1 && self.sample_pattern == SamplePattern::Grid {
+            return world.color_at(&self.ray_for_pixel(x, y));
+        }
+
+        let offsets = self.sample_offsets(x, y);
+        let sum = offsets.iter().fold(Color::default(), |acc, (dx, dy)| {
+            acc + &world.color_at(&self.ray_for_pixel_offset(x, y, *dx, *dy))
+        });
+        sum * (1.0 / offsets.len() as f64)
+    }
+
+    fn write_row(&self, image: &mut Canvas, y: usize, row: Vec<Color>) {
+        for (x, color) in row.into_iter().enumerate() {
+            image.write_pixel(x, y, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    use float_cmp::approx_eq;
+
+    use crate::raytracer::{color::Color, objects::{lights::{AreaLight, PointLight}, materials::Material, object::Object, sphere::Sphere}, transformation, world::tests::default_world};
+
+    use super::*;
+
+    #[test]
+    fn new_sets_members() {
+        // Given
+        let hsize = 160;
+        let vsize = 120;
+        let field_of_view = PI / 2.0;
+
+        // When
+        let c = Camera::new(hsize, vsize, field_of_view);
+
+        // Then
+        assert_eq!(c.hsize(), 160);
+        assert_eq!(c.vsize(), 120);
+        assert_eq!(c.field_of_view(), PI / 2.0);
+        assert_eq!(c.transform(), &Matrix::identity(4, 4));
+    }
+
+    #[test]
+    fn pixel_size_for_horizontal_canvas() {
+        // When
+        let c = Camera::new(200, 125, PI / 2.0);
+
+        // Then
+        assert!(approx_eq!(f64, c.pixel_size(), 0.01, epsilon = 0.00001));
+    }
+
+    #[test]
+    fn pixel_size_for_vertical_canvas() {
+        // When
+        let c = Camera::new(125, 200, PI / 2.0);
+
+        // Then
+        assert!(approx_eq!(f64, c.pixel_size(), 0.01, epsilon = 0.00001));
+    }
+
+    #[test]
+    fn ray_for_pixel_through_center_of_canvas() {
+        // Given
+        let c = Camera::new(201, 101, PI / 2.0);
+
+        // When
+        let r = c.ray_for_pixel(100, 50);
+
+        // Then
+        assert_eq!(r.origin(), &Tuple::point(0.0, 0.0, 0.0));
+        assert_eq!(r.direction(), &Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn ray_for_pixel_through_corner_of_canvas() {
+        // Given
+        let c = Camera::new(201, 101, PI / 2.0);
+
+        // When
+        let r = c.ray_for_pixel(0, 0);
+
+        // Then
+        assert_eq!(r.origin(), &Tuple::point(0.0, 0.0, 0.0));
+        assert_eq!(r.direction(), &Tuple::vector(0.66519, 0.33259, -0.66851));
+    }
+
+    #[test]
+    fn ray_for_pixel_with_transformed_camera() {
+        // Given
+        let mut c = Camera::new(201, 101, PI / 2.0);
+        c.set_transform(transformation::rotation_y(PI / 4.0) * &transformation::translation(0.0, -2.0, 5.0));
+
+        // When
+        let r = c.ray_for_pixel(100, 50);
+
+        // Then
+        assert_eq!(r.origin(), &Tuple::point(0.0, 2.0, -5.0));
+        assert_eq!(r.direction(), &Tuple::vector(FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn render_world_with_camera() {
+        // Given
+        let w = default_world();
+
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        let from = Tuple::point(0.0, 0.0, -5.0);
+        let to = Tuple::point(0.0, 0.0, 0.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+        c.set_transform(transformation::view_transform(&from, &to, &up));
+
+        // When
+        let image = c.render(&w);
+
+        // Then
+        assert_eq!(image.pixel_at(5, 5), Color::new(0.38066, 0.47583, 0.2855));
+    }
+
+    #[test]
+    fn set_threads_uses_at_least_one_thread() {
+        // Given
+        let mut c = Camera::new(160, 120, PI / 2.0);
+
+        // When
+        c.set_threads(0);
+
+        // Then
+        assert_eq!(c.threads(), 1);
+    }
+
+    #[test]
+    fn render_with_threads_matches_single_threaded_render() {
+        // Given
+        let w = default_world();
+        let mut c = Camera::new(21, 17, PI / 2.0);
+        let from = Tuple::point(0.0, 0.0, -5.0);
+        let to = Tuple::point(0.0, 0.0, 0.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+        c.set_transform(transformation::view_transform(&from, &to, &up));
+        let expected = c.render(&w);
+
+        // When
+        c.set_threads(4);
+        let image = c.render(&w);
+
+        // Then
+        assert_eq!(image.pixels(), expected.pixels());
+    }
+
+    #[test]
+    fn render_with_threads_matches_single_threaded_render_with_random_samples() {
+        // Given
+        let w = default_world();
+        let mut c = Camera::new(21, 17, PI / 2.0);
+        let from = Tuple::point(0.0, 0.0, -5.0);
+        let to = Tuple::point(0.0, 0.0, 0.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+        c.set_transform(transformation::view_transform(&from, &to, &up));
+        c.set_samples(5);
+
+        for pattern in [SamplePattern::Jittered, SamplePattern::Random] {
+            c.set_sample_pattern(pattern);
+            c.set_threads(1);
+            let expected = c.render(&w);
+
+            // When
+            c.set_threads(4);
+            let image = c.render(&w);
+
+            // Then
+            assert_eq!(image.pixels(), expected.pixels(), "{:?}", pattern);
+        }
+    }
+
+    #[test]
+    fn render_with_threads_matches_single_threaded_render_with_jittered_area_light() {
+        // Given
+        let mut w = default_world();
+        let mut light = AreaLight::new(Tuple::point(-11.0, 9.0, -11.0), Tuple::vector(2.0, 0.0, 0.0), 4, Tuple::vector(0.0, 2.0, 0.0), 4, Color::new(1.0, 1.0, 1.0));
+        light.set_jitter(true);
+        w.add_light(Box::new(light));
+        let mut c = Camera::new(21, 17, PI / 2.0);
+        let from = Tuple::point(0.0, 0.0, -5.0);
+        let to = Tuple::point(0.0, 0.0, 0.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+        c.set_transform(transformation::view_transform(&from, &to, &up));
+        let expected = c.render(&w);
+
+        // When
+        c.set_threads(4);
+        let image = c.render(&w);
+        let again = c.render(&w);
+
+        // Then
+        assert_eq!(image.pixels(), expected.pixels());
+        assert_eq!(again.pixels(), expected.pixels());
+    }
+
+    #[test]
+    fn random_sample_offsets_repeat_for_same_pixel() {
+        // Given
+        let mut c = Camera::new(160, 120, PI / 2.0);
+        c.set_samples(4);
+        c.set_sample_pattern(SamplePattern::Random);
+
+        // When
+        let first = c.sample_offsets(3, 5);
+        let second = c.sample_offsets(3, 5);
+        let neighbour = c.sample_offsets(4, 5);
+
+        // Then
+        assert_eq!(first, second);
+        assert_ne!(first, neighbour);
+    }
+
+    #[test]
+    fn render_with_progress_reports_every_row() {
+        // Given
+        let w = default_world();
+        let mut c = Camera::new(5, 7, PI / 2.0);
+        c.set_threads(3);
+        let reported = AtomicUsize::new(0);
+        let last = AtomicUsize::new(0);
+
+        // When
+        c.render_with_progress(&w, &|rows| {
+            reported.fetch_add(1, Ordering::Relaxed);
+            last.fetch_max(rows, Ordering::Relaxed);
+        });
+
+        // Then
+        assert_eq!(reported.load(Ordering::Relaxed), 7);
+        assert_eq!(last.load(Ordering::Relaxed), 7);
+    }
+
+    #[test]
+    fn new_samples_pixel_center_once() {
+        // When
+        let c = Camera::new(160, 120, PI / 2.0);
+
+        // Then
+        assert_eq!(c.samples(), 1);
+        assert_eq!(c.sample_pattern(), SamplePattern::Grid);
+        assert_eq!(c.sample_offsets(0, 0), vec![(0.5, 0.5)]);
+    }
+
+    #[test]
+    fn set_samples_uses_at_least_one_sample() {
+        // Given
+        let mut c = Camera::new(160, 120, PI / 2.0);
+
+        // When
+        c.set_samples(0);
+
+        // Then
+        assert_eq!(c.samples(), 1);
+    }
+
+    #[test]
+    fn grid_sample_offsets_are_cell_centers() {
+        // Given
+        let mut c = Camera::new(160, 120, PI / 2.0);
+
+        // When
+        c.set_samples(4);
+        let square = c.sample_offsets(0, 0);
+        c.set_samples(6);
+        let rectangle = c.sample_offsets(0, 0);
+
+        // Then
+        assert_eq!(square, vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]);
+        let rows = [0.5 / 3.0, 1.5 / 3.0, 2.5 / 3.0];
+        assert_eq!(rectangle, rows.iter().flat_map(|dy| [(0.25, *dy), (0.75, *dy)]).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn grid_sample_offsets_for_prime_count_vary_in_both_directions() {
+        // Given
+        let mut c = Camera::new(160, 120, PI / 2.0);
+
+        // When
+        c.set_samples(7);
+        let offsets = c.sample_offsets(0, 0);
+
+        // Then
+        let mut xs: Vec<f64> = offsets.iter().map(|(dx, _)| *dx).collect();
+        let mut ys: Vec<f64> = offsets.iter().map(|(_, dy)| *dy).collect();
+        xs.dedup();
+        ys.dedup();
+        assert_eq!(offsets.len(), 7);
+        assert!(xs.len() > 1, "{:?}", offsets);
+        assert_eq!(ys, vec![0.5 / 3.0, 1.5 / 3.0, 2.5 / 3.0]);
+        assert!(offsets.iter().all(|(dx, dy)| (0.0..1.0).contains(dx) && (0.0..1.0).contains(dy)));
+    }
+
+    #[test]
+    fn jittered_sample_offsets_stay_in_their_cells() {
+        // Given
+        let mut c = Camera::new(160, 120, PI / 2.0);
+        c.set_samples(9);
+        c.set_sample_pattern(SamplePattern::Jittered);
+
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (17, 42), (159, 119)] {
+            // When
+            let offsets = c.sample_offsets(x, y);
+
+            // Then
+            assert_eq!(offsets.len(), 9);
+            for (i, (dx, dy)) in offsets.into_iter().enumerate() {
+                let (column, row) = ((i % 3) as f64, (i / 3) as f64);
+                assert!(dx >= column / 3.0 && dx < (column + 1.0) / 3.0, "sample {}: {}", i, dx);
+                assert!(dy >= row / 3.0 && dy < (row + 1.0) / 3.0, "sample {}: {}", i, dy);
+            }
+        }
+    }
+
+    #[test]
+    fn random_sample_offsets_stay_in_pixel() {
+        // Given
+        let mut c = Camera::new(160, 120, PI / 2.0);
+        c.set_samples(5);
+        c.set_sample_pattern(SamplePattern::Random);
+
+        // When
+        let offsets = c.sample_offsets(0, 0);
+
+        // Then
+        assert_eq!(offsets.len(), 5);
+        assert!(offsets.iter().all(|(dx, dy)| (0.0..1.0).contains(dx) && (0.0..1.0).contains(dy)));
+    }
+
+    #[test]
+    fn ray_for_pixel_offset_through_corner_of_pixel() {
+        // Given
+        let c = Camera::new(200, 200, PI / 2.0);
+
+        // When
+        let r = c.ray_for_pixel_offset(0, 0, 0.0, 0.0);
+
+        // Then
+        assert_eq!(r.origin(), &Tuple::point(0.0, 0.0, 0.0));
+        assert_eq!(r.direction(), &Tuple::vector(1.0, 1.0, -1.0).normalize());
+    }
+
+    #[test]
+    fn supersampling_blends_silhouette_pixels() {
+        // Given
+        // a sphere lit only by its ambient color, so it's exactly white on a black background
+        let mut sphere = Sphere::default();
+        sphere.set_material(Material::new(Color::new(1.0, 1.0, 1.0), 1.0, 0.0, 0.0, 200.0));
+        let light = PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let w = World::new(vec![Box::new(sphere)], vec![Box::new(light)]);
+        let mut c = Camera::new(21, 21, PI / 3.0);
+        c.set_transform(transformation::view_transform(&Tuple::point(0.0, 0.0, -5.0), &Tuple::point(0.0, 0.0, 0.0), &Tuple::vector(0.0, 1.0, 0.0)));
+        let is_fractional = |p: &Color| p.red() > 0.0 && p.red() < 1.0;
+
+        for pattern in [SamplePattern::Grid, SamplePattern::Jittered, SamplePattern::Random] {
+            c.set_samples(1);
+            c.set_sample_pattern(SamplePattern::Grid);
+            let aliased = c.render(&w);
+
+            // When
+            c.set_samples(16);
+            c.set_sample_pattern(pattern);
+            let smooth = c.render(&w);
+
+            // Then
+            assert!(!aliased.pixels().iter().any(is_fractional));
+            assert!(smooth.pixels().iter().any(is_fractional), "{:?}", pattern);
+            assert_eq!(smooth.pixel_at(10, 10), Color::new(1.0, 1.0, 1.0));
+            assert_eq!(smooth.pixel_at(0, 0), Color::new(0.0, 0.0, 0.0));
+        }
+    }
+}
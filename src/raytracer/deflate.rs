@@ -0,0 +1,307 @@
+//! A small zlib (RFC 1950) encoder producing a single deflate (RFC 1951) block with the fixed Huffman codes.
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const HASH_SIZE: usize = 1 << 15;
+/// How many earlier positions with the same hash are tried before settling for the best match so far.
+const MAX_CHAIN: usize = 64;
+const END_OF_BLOCK: u16 = 256;
+
+const LENGTH_BASE: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA_BITS: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+/// Compresses `data` into a zlib stream.
+pub fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    // CMF: deflate with a 32K window, FLG: no dictionary and a check value making the header a multiple of 31
+    writer.bytes.extend_from_slice(&[0x78, 0x01]);
+
+    // a single final block using the fixed Huffman codes
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+    deflate(data, &mut writer);
+    writer.write_literal_length(END_OF_BLOCK);
+
+    let mut bytes = writer.finish();
+    bytes.extend_from_slice(&adler32(data).to_be_bytes());
+    bytes
+}
+
+/// The Adler-32 checksum zlib streams end with.
+pub fn adler32(data: &[u8]) -> u32 {
+    const MOD_ADLER: u32 = 65521;
+    // 5552 is the largest number of bytes that can be summed before b can overflow
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= MOD_ADLER;
+        b %= MOD_ADLER;
+    }
+    (b << 16) | a
+}
+
+// LZ77 with hash chains: every position is remembered under the hash of its next three bytes,
+// and the longest earlier match within the window is replaced by a length/distance pair.
+fn deflate(data: &[u8], writer: &mut BitWriter) {
+    let mut head = vec![usize::MAX; HASH_SIZE];
+    let mut previous = vec![usize::MAX; WINDOW_SIZE];
+    let mut position = 0;
+    while position < data.len() {
+        let (length, distance) = longest_match(data, position, &head, &previous);
+        if length >= MIN_MATCH {
+            writer.write_match(length, distance);
+            for p in position..position + length {
+                insert(data, p, &mut head, &mut previous);
+            }
+            position += length;
+        } else {
+            writer.write_literal_length(data[position] as u16);
+            insert(data, position, &mut head, &mut previous);
+            position += 1;
+        }
+    }
+}
+
+fn insert(data: &[u8], position: usize, head: &mut [usize], previous: &mut [usize]) {
+    if position + MIN_MATCH <= data.len() {
+        let hash = hash(&data[position..position + MIN_MATCH]);
+        previous[position % WINDOW_SIZE] = head[hash];
+        head[hash] = position;
+    }
+}
+
+fn longest_match(data: &[u8], position: usize, head: &[usize], previous: &[usize]) -> (usize, usize) {
+    if position + MIN_MATCH > data.len() {
+        return (0, 0);
+    }
+
+    let max_length = MAX_MATCH.min(data.len() - position);
+    let (mut best_length, mut best_distance) = (0, 0);
+    let mut candidate = head[hash(&data[position..position + MIN_MATCH])];
+    for _ in 0..MAX_CHAIN {
+        if candidate == usize::MAX || position - candidate > WINDOW_SIZE {
+            break;
+        }
+
+        let length = data[candidate..].iter()
+            .zip(&data[position..position + max_length])
+            .take_while(|(a, b)| a == b)
+            .count();
+        if length > best_length {
+            (best_length, best_distance) = (length, position - candidate);
+            if length == max_length {
+                break;
+            }
+        }
+
+        // the chain slot may have been reused by a newer position once the window wrapped around
+        let next = previous[candidate % WINDOW_SIZE];
+        if next == usize::MAX || next >= candidate {
+            break;
+        }
+        candidate = next;
+    }
+    (best_length, best_distance)
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let value = (bytes[0] as usize) << 16 | (bytes[1] as usize) << 8 | bytes[2] as usize;
+    (value.wrapping_mul(2654435761) >> 9) % HASH_SIZE
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        Self { bytes: vec![], buffer: 0, count: 0 }
+    }
+
+    /// Writes the lowest `count` bits of `value`, least significant bit first.
+    fn write_bits(&mut self, value: u32, count: u32) {
+        self.buffer |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Huffman codes are packed starting with their most significant bit.
+    fn write_code(&mut self, code: u32, length: u32) {
+        self.write_bits(code.reverse_bits() >> (32 - length), length);
+    }
+
+    fn write_literal_length(&mut self, symbol: u16) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn write_match(&mut self, length: usize, distance: usize) {
+        let index = LENGTH_BASE.partition_point(|base| *base as usize <= length) - 1;
+        self.write_literal_length(257 + index as u16);
+        self.write_bits((length - LENGTH_BASE[index] as usize) as u32, LENGTH_EXTRA_BITS[index] as u32);
+
+        let index = DISTANCE_BASE.partition_point(|base| *base as usize <= distance) - 1;
+        self.write_code(index as u32, 5);
+        self.write_bits((distance - DISTANCE_BASE[index] as usize) as u32, DISTANCE_EXTRA_BITS[index] as u32);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Decodes a zlib stream made of stored and fixed Huffman blocks, which covers everything `zlib_compress` writes.
+    pub fn zlib_decompress(stream: &[u8]) -> Vec<u8> {
+        assert_eq!(((stream[0] as u16) << 8 | stream[1] as u16) % 31, 0, "invalid zlib header");
+        assert_eq!(stream[0] & 0x0f, 8, "not deflate");
+
+        let mut reader = BitReader { bytes: &stream[2..], position: 0 };
+        let mut out: Vec<u8> = vec![];
+        loop {
+            let last = reader.bits(1) == 1;
+            match reader.bits(2) {
+                0 => {
+                    reader.position = reader.position.div_ceil(8) * 8;
+                    let length = reader.bits(16) as usize;
+                    assert_eq!(reader.bits(16) as usize, !length & 0xffff);
+                    for _ in 0..length {
+                        out.push(reader.bits(8) as u8);
+                    }
+                }
+                1 => loop {
+                    let symbol = reader.literal_length();
+                    if symbol < 256 {
+                        out.push(symbol as u8);
+                        continue;
+                    }
+                    if symbol == END_OF_BLOCK as usize {
+                        break;
+                    }
+                    let index = symbol - 257;
+                    let length = LENGTH_BASE[index] as usize + reader.bits(LENGTH_EXTRA_BITS[index] as u32) as usize;
+                    let index = reader.code(5) as usize;
+                    let distance = DISTANCE_BASE[index] as usize + reader.bits(DISTANCE_EXTRA_BITS[index] as u32) as usize;
+                    let start = out.len() - distance;
+                    for i in 0..length {
+                        out.push(out[start + i]);
+                    }
+                },
+                other => panic!("unsupported block type {}", other),
+            }
+            if last {
+                break;
+            }
+        }
+
+        let checksum_at = 2 + reader.position.div_ceil(8);
+        let checksum = u32::from_be_bytes(stream[checksum_at..checksum_at + 4].try_into().unwrap());
+        assert_eq!(checksum, adler32(&out), "adler32 mismatch");
+        out
+    }
+
+    struct BitReader<'a> {
+        bytes: &'a [u8],
+        position: usize,
+    }
+
+    impl BitReader<'_> {
+        fn bits(&mut self, count: u32) -> u32 {
+            let mut value = 0;
+            for i in 0..count {
+                let bit = (self.bytes[self.position / 8] >> (self.position % 8)) & 1;
+                value |= (bit as u32) << i;
+                self.position += 1;
+            }
+            value
+        }
+
+        fn code(&mut self, length: u32) -> u32 {
+            (0..length).fold(0, |code, _| code << 1 | self.bits(1))
+        }
+
+        fn literal_length(&mut self) -> usize {
+            let code = self.code(7) as usize;
+            if code <= 0x17 {
+                return 256 + code;
+            }
+            let code = code << 1 | self.bits(1) as usize;
+            match code {
+                0x30..=0xbf => code - 0x30,
+                0xc0..=0xc7 => 280 + code - 0xc0,
+                _ => 144 + (code << 1 | self.bits(1) as usize) - 0x190,
+            }
+        }
+    }
+
+    #[test]
+    fn adler32_of_known_input() {
+        // When & Then
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+    }
+
+    #[test]
+    fn adler32_of_long_input_does_not_overflow() {
+        // Given
+        let data = vec![0xff; 100_000];
+
+        // When & Then
+        assert_eq!(adler32(&data), 0x149a302c);
+    }
+
+    #[test]
+    fn zlib_compress_round_trips() {
+        // Given
+        let inputs: [Vec<u8>; 4] = [
+            vec![],
+            b"a".to_vec(),
+            b"abcabcabcabcabcabc hello hello hello world".to_vec(),
+            (0..100_000u64).map(|i| (i * i % 251) as u8 ^ (i / 1000) as u8).collect(),
+        ];
+
+        for input in inputs {
+            // When
+            let compressed = zlib_compress(&input);
+
+            // Then
+            assert_eq!(zlib_decompress(&compressed), input);
+        }
+    }
+
+    #[test]
+    fn zlib_compress_shrinks_repetitive_data() {
+        // Given
+        let data = [10u8, 20, 30].repeat(10_000);
+
+        // When
+        let compressed = zlib_compress(&data);
+
+        // Then
+        assert!(compressed.len() < data.len() / 50, "{} bytes", compressed.len());
+        assert_eq!(zlib_decompress(&compressed), data);
+    }
+}
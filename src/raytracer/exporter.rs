@@ -1,34 +1,55 @@
-use std::io::Write;
+use std::io::{Error, ErrorKind, Write};
 
-use super::canvas;
+use super::{canvas, deflate};
 
 pub trait Exporter {
     fn export(&self, canvas: &canvas::Canvas, writer: &mut dyn Write) -> std::io::Result<()>;
 }
 
-pub struct PPMExporter {}
+/// How the samples of a PPM image are written: as decimal text (P3) or as raw bytes (P6).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PPMEncoding {
+    Ascii,
+    Binary,
+}
+
+pub struct PPMExporter {
+    encoding: PPMEncoding,
+    max_value: u16,
+}
 
 const MAX_PPM_LINE_LENGTH: usize = 70;
 
 impl PPMExporter {
     pub fn new() -> Self {
-        Self {}
+        Self::with_options(PPMEncoding::Ascii, u8::MAX as u16)
     }
-}
 
-impl Exporter for PPMExporter {
-    fn export(&self, canvas: &canvas::Canvas, writer: &mut dyn Write) -> std::io::Result<()> {
+    /// Creates an exporter writing samples in the range 0..=max_value, a maximum value of zero is raised to one.
+    pub fn with_options(encoding: PPMEncoding, max_value: u16) -> Self {
+        Self { encoding, max_value: max_value.max(1) }
+    }
+
+    pub fn encoding(&self) -> PPMEncoding {
+        self.encoding
+    }
+
+    pub fn max_value(&self) -> u16 {
+        self.max_value
+    }
+
+    fn export_ascii(&self, canvas: &canvas::Canvas, writer: &mut dyn Write) -> std::io::Result<()> {
         let mut buf = itoa::Buffer::new();
-        
-        writer.write_all(format!("P3\n{} {}\n255\n", canvas.width(), canvas.height()).as_bytes())?;
 
+        // lines end with every row of the image and are wrapped before they'd grow past the limit,
+        // which depends on how many digits the samples take up
+        let row_length = canvas.width() * 3;
         let mut current_line_length = 0;
-        let pixels = canvas.pixels();
-        for el in pixels.iter() {
-            let colors: [u8; 3] = [get_out_val(el.red()), get_out_val(el.green()), get_out_val(el.blue())];
-            for color in colors.iter() {
-                writer.write_all(buf.format(*color).as_bytes())?;
-                if (current_line_length + 1) % (canvas.width() * 3) == 0 || current_line_length + 1 >= MAX_PPM_LINE_LENGTH {
+        let samples = canvas.pixels().iter().flat_map(|el| [el.red(), el.green(), el.blue()]);
+        for (i, sample) in samples.enumerate() {
+            let digits = buf.format(get_out_val(sample, self.max_value));
+            if current_line_length > 0 {
+                if current_line_length + 1 + digits.len() > MAX_PPM_LINE_LENGTH {
                     writer.write_all(b"\n")?;
                     current_line_length = 0;
                 } else {
@@ -36,22 +57,165 @@ impl Exporter for PPMExporter {
                     current_line_length += 1;
                 }
             }
+            writer.write_all(digits.as_bytes())?;
+            current_line_length += digits.len();
+
+            if (i + 1) % row_length == 0 {
+                writer.write_all(b"\n")?;
+                current_line_length = 0;
+            }
         }
         Ok(())
     }
+
+    fn export_binary(&self, canvas: &canvas::Canvas, writer: &mut dyn Write) -> std::io::Result<()> {
+        // samples take two bytes, most significant first, once they don't fit into one
+        let bytes_per_sample = if self.max_value > u8::MAX as u16 { 2 } else { 1 };
+        let mut data = Vec::with_capacity(canvas.pixels().len() * 3 * bytes_per_sample);
+        for el in canvas.pixels() {
+            for color in [el.red(), el.green(), el.blue()].map(|c| get_out_val(c, self.max_value)) {
+                if bytes_per_sample == 2 {
+                    data.extend_from_slice(&color.to_be_bytes());
+                } else {
+                    data.push(color as u8);
+                }
+            }
+        }
+        writer.write_all(&data)
+    }
+}
+
+impl Default for PPMExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Exporter for PPMExporter {
+    fn export(&self, canvas: &canvas::Canvas, writer: &mut dyn Write) -> std::io::Result<()> {
+        let magic = match self.encoding {
+            PPMEncoding::Ascii => "P3",
+            PPMEncoding::Binary => "P6",
+        };
+        writer.write_all(format!("{}\n{} {}\n{}\n", magic, canvas.width(), canvas.height(), self.max_value).as_bytes())?;
+
+        match self.encoding {
+            PPMEncoding::Ascii => self.export_ascii(canvas, writer),
+            PPMEncoding::Binary => self.export_binary(canvas, writer),
+        }
+    }
+}
+
+/// The number of bits per channel in a PNG image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BitDepth {
+    Eight,
+    Sixteen,
+}
+
+pub struct PNGExporter {
+    bit_depth: BitDepth,
+}
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+const PNG_COLOR_TYPE_RGB: u8 = 2;
+const PNG_FILTER_NONE: u8 = 0;
+
+impl PNGExporter {
+    pub fn new() -> Self {
+        Self::with_bit_depth(BitDepth::Eight)
+    }
+
+    pub fn with_bit_depth(bit_depth: BitDepth) -> Self {
+        Self { bit_depth }
+    }
+
+    pub fn bit_depth(&self) -> BitDepth {
+        self.bit_depth
+    }
+
+    fn bits(&self) -> u8 {
+        match self.bit_depth {
+            BitDepth::Eight => 8,
+            BitDepth::Sixteen => 16,
+        }
+    }
+}
+
+impl Default for PNGExporter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-fn get_out_val(px: f64) -> u8 {
+impl Exporter for PNGExporter {
+    fn export(&self, canvas: &canvas::Canvas, writer: &mut dyn Write) -> std::io::Result<()> {
+        if canvas.width() == 0 || canvas.height() == 0 {
+            return Err(Error::new(ErrorKind::InvalidInput, "PNG images can't be empty"));
+        }
+        let width = u32::try_from(canvas.width()).map_err(|_| Error::new(ErrorKind::InvalidInput, "canvas is too wide for PNG"))?;
+        let height = u32::try_from(canvas.height()).map_err(|_| Error::new(ErrorKind::InvalidInput, "canvas is too high for PNG"))?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        // bit depth and color type, followed by the default compression, filter and (no) interlace methods
+        header.extend_from_slice(&[self.bits(), PNG_COLOR_TYPE_RGB, 0, 0, 0]);
+
+        // every scanline starts with the filter type used for it
+        let bytes_per_pixel = 3 * self.bits() as usize / 8;
+        let mut scanlines = Vec::with_capacity(canvas.height() * (1 + canvas.width() * bytes_per_pixel));
+        for row in canvas.pixels().chunks(canvas.width()) {
+            scanlines.push(PNG_FILTER_NONE);
+            for pixel in row {
+                for channel in [pixel.red(), pixel.green(), pixel.blue()] {
+                    match self.bit_depth {
+                        BitDepth::Eight => scanlines.push(get_out_val(channel, u8::MAX as u16) as u8),
+                        BitDepth::Sixteen => scanlines.extend_from_slice(&get_out_val(channel, u16::MAX).to_be_bytes()),
+                    }
+                }
+            }
+        }
+
+        writer.write_all(&PNG_SIGNATURE)?;
+        write_png_chunk(writer, b"IHDR", &header)?;
+        write_png_chunk(writer, b"IDAT", &deflate::zlib_compress(&scanlines))?;
+        write_png_chunk(writer, b"IEND", &[])
+    }
+}
+
+fn write_png_chunk(writer: &mut dyn Write, chunk_type: &[u8; 4], data: &[u8]) -> std::io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(chunk_type)?;
+    writer.write_all(data)?;
+    // the checksum covers the chunk type and data, but not the length
+    let crc = !crc32_update(crc32_update(!0, chunk_type), data);
+    writer.write_all(&crc.to_be_bytes())
+}
+
+// The CRC-32 used by PNG (and zlib's crc32), computed bitwise since chunks are only checksummed once.
+fn crc32_update(mut crc: u32, data: &[u8]) -> u32 {
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb88320 } else { crc >> 1 };
+        }
+    }
+    crc
+}
+
+/// Clamps a color channel to 0..=1 and scales it to 0..=max_value.
+fn get_out_val(px: f64, max_value: u16) -> u16 {
     match px {
         px if px <= 0.0 => 0,
-        px if px >= 1.0 => 255,
-        px => (px * 255.0).round() as u8,
+        px if px >= 1.0 => max_value,
+        px => (px * max_value as f64).round() as u16,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::raytracer::color::Color;
+    use crate::raytracer::{color::Color, deflate::tests::zlib_decompress};
 
     use super::*;
     use std::io::{BufWriter, BufReader, BufRead};
@@ -123,13 +287,38 @@ mod tests {
             lines.next().unwrap().unwrap();
         }
 
-        // there should be just zeroes with a space like "0 " except the last character
-        let mut line = lines.next().unwrap().unwrap();
-        assert_eq!(line.len(), MAX_PPM_LINE_LENGTH * 2 - 1);
+        // zeroes separated by spaces fit 35 samples into a line
+        assert_eq!(lines.next().unwrap().unwrap(), ["0"; 35].join(" "));
+        assert_eq!(lines.next().unwrap().unwrap(), ["0"; 35].join(" "));
 
         // the last line should contain the overflow
-        line = lines.next().unwrap().unwrap();
-        assert_eq!(line.len(), 4);
+        assert_eq!(lines.next().unwrap().unwrap(), "0 0");
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn ppm_export_splits_sixteen_bit_samples_by_characters() {
+        // Given
+        let exporter = PPMExporter::with_options(PPMEncoding::Ascii, 65535);
+        let mut buffer = Vec::new();
+        let mut canvas = canvas::Canvas::new(20, 2);
+        for y in 0..2 {
+            for x in 0..20 {
+                canvas.write_pixel(x, y, Color::new(1.0, 1.0, 1.0));
+            }
+        }
+
+        // When
+        exporter.export(&canvas, &mut buffer).unwrap();
+
+        // Then
+        let text = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = text.lines().skip(3).collect();
+        assert!(lines.iter().all(|line| line.len() <= MAX_PPM_LINE_LENGTH), "{:?}", lines);
+        // eleven samples of "65535" take up 65 characters, a twelfth wouldn't fit anymore
+        assert_eq!(lines[0], ["65535"; 11].join(" "));
+        let samples: usize = lines.iter().map(|line| line.split(' ').count()).sum();
+        assert_eq!(samples, 20 * 2 * 3);
     }
 
     #[test]
@@ -147,4 +336,229 @@ mod tests {
         let chars = buffer.get_ref().as_slice();
         assert!(chars.iter().last().unwrap().eq(&b'\n'))
     }
+
+    #[test]
+    fn ppm_export_writes_max_value() {
+        // Given
+        let exporter = PPMExporter::with_options(PPMEncoding::Ascii, 65535);
+        let mut buffer = Vec::new();
+        let mut canvas = canvas::Canvas::new(2, 1);
+        canvas.write_pixel(0, 0, Color::new(1.5, 0.5, 0.0));
+        canvas.write_pixel(1, 0, Color::new(0.25, -1.0, 1.0));
+
+        // When
+        exporter.export(&canvas, &mut buffer).unwrap();
+
+        // Then
+        assert_eq!(String::from_utf8(buffer).unwrap(), "P3\n2 1\n65535\n65535 32768 0 16384 0 65535\n");
+    }
+
+    #[test]
+    fn ppm_export_binary_writes_bytes() {
+        // Given
+        let exporter = PPMExporter::with_options(PPMEncoding::Binary, 255);
+        let mut buffer = Vec::new();
+        let mut canvas = canvas::Canvas::new(2, 1);
+        canvas.write_pixel(0, 0, Color::new(1.5, 0.5, 0.0));
+        canvas.write_pixel(1, 0, Color::new(0.25, -1.0, 1.0));
+
+        // When
+        exporter.export(&canvas, &mut buffer).unwrap();
+
+        // Then
+        let header = b"P6\n2 1\n255\n";
+        assert_eq!(buffer[..header.len()], header[..]);
+        assert_eq!(buffer[header.len()..], [255, 128, 0, 64, 0, 255]);
+    }
+
+    #[test]
+    fn ppm_export_binary_writes_two_bytes_per_sample_above_255() {
+        // Given
+        let exporter = PPMExporter::with_options(PPMEncoding::Binary, 1000);
+        let mut buffer = Vec::new();
+        let mut canvas = canvas::Canvas::new(1, 1);
+        canvas.write_pixel(0, 0, Color::new(1.0, 0.5, 0.0));
+
+        // When
+        exporter.export(&canvas, &mut buffer).unwrap();
+
+        // Then
+        let header = b"P6\n1 1\n1000\n";
+        assert_eq!(buffer[..header.len()], header[..]);
+        assert_eq!(buffer[header.len()..], [0x03, 0xe8, 0x01, 0xf4, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn with_options_raises_zero_max_value() {
+        // When
+        let exporter = PPMExporter::with_options(PPMEncoding::Binary, 0);
+
+        // Then
+        assert_eq!(exporter.encoding(), PPMEncoding::Binary);
+        assert_eq!(exporter.max_value(), 1);
+    }
+
+    #[test]
+    fn get_out_val_clamps_and_scales() {
+        // When & Then
+        assert_eq!(get_out_val(-0.1, 255), 0);
+        assert_eq!(get_out_val(0.5, 255), 128);
+        assert_eq!(get_out_val(1.1, 255), 255);
+        assert_eq!(get_out_val(0.5, 15), 8);
+        assert_eq!(get_out_val(1.0, 65535), 65535);
+    }
+
+    struct DecodedPng {
+        width: u32,
+        height: u32,
+        bit_depth: u8,
+        color_type: u8,
+        samples: Vec<u16>,
+    }
+
+    // checks the signature and every chunk's CRC, then undoes the compression and the filters
+    fn decode_png(bytes: &[u8]) -> DecodedPng {
+        assert_eq!(bytes[..8], PNG_SIGNATURE);
+
+        let mut header = vec![];
+        let mut data = vec![];
+        let mut rest = &bytes[8..];
+        let mut chunk_types = vec![];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let chunk_type: [u8; 4] = rest[4..8].try_into().unwrap();
+            let chunk_data = &rest[8..8 + length];
+            let crc = u32::from_be_bytes(rest[8 + length..12 + length].try_into().unwrap());
+            assert_eq!(crc, !crc32_update(!0, &rest[4..8 + length]), "bad CRC in {:?}", chunk_type);
+
+            match &chunk_type {
+                b"IHDR" => header = chunk_data.to_vec(),
+                b"IDAT" => data.extend_from_slice(chunk_data),
+                _ => {}
+            }
+            chunk_types.push(chunk_type);
+            rest = &rest[12 + length..];
+        }
+        assert_eq!(chunk_types.first(), Some(b"IHDR"));
+        assert_eq!(chunk_types.last(), Some(b"IEND"));
+
+        let width = u32::from_be_bytes(header[0..4].try_into().unwrap());
+        let height = u32::from_be_bytes(header[4..8].try_into().unwrap());
+        let bit_depth = header[8];
+        let bytes_per_sample = bit_depth as usize / 8;
+        let stride = width as usize * 3 * bytes_per_sample;
+
+        let scanlines = zlib_decompress(&data);
+        assert_eq!(scanlines.len(), height as usize * (stride + 1));
+        let mut samples = vec![];
+        for line in scanlines.chunks(stride + 1) {
+            assert_eq!(line[0], PNG_FILTER_NONE);
+            for sample in line[1..].chunks(bytes_per_sample) {
+                samples.push(sample.iter().fold(0, |value, byte| value << 8 | *byte as u16));
+            }
+        }
+
+        DecodedPng { width, height, bit_depth, color_type: header[9], samples }
+    }
+
+    fn gradient_canvas(width: usize, height: usize) -> canvas::Canvas {
+        let mut canvas = canvas::Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                canvas.write_pixel(x, y, Color::new(x as f64 / width as f64, y as f64 / height as f64, 1.2 - (x + y) as f64 / 10.0));
+            }
+        }
+        canvas
+    }
+
+    #[test]
+    fn crc32_of_known_input() {
+        // When & Then
+        assert_eq!(!crc32_update(!0, b"123456789"), 0xcbf43926);
+        assert_eq!(!crc32_update(!0, b"IEND"), 0xae426082);
+    }
+
+    #[test]
+    fn png_export_writes_header() {
+        // Given
+        let exporter = PNGExporter::new();
+        let mut buffer = Vec::new();
+        let canvas = canvas::Canvas::new(5, 3);
+
+        // When
+        exporter.export(&canvas, &mut buffer).unwrap();
+
+        // Then
+        let png = decode_png(&buffer);
+        assert_eq!(png.width, 5);
+        assert_eq!(png.height, 3);
+        assert_eq!(png.bit_depth, 8);
+        assert_eq!(png.color_type, PNG_COLOR_TYPE_RGB);
+    }
+
+    #[test]
+    fn png_export_writes_pixels() {
+        // Given
+        let exporter = PNGExporter::new();
+        let mut buffer = Vec::new();
+        let canvas = gradient_canvas(13, 7);
+
+        // When
+        exporter.export(&canvas, &mut buffer).unwrap();
+
+        // Then
+        let png = decode_png(&buffer);
+        let expected: Vec<u16> = canvas.pixels().iter()
+            .flat_map(|p| [p.red(), p.green(), p.blue()])
+            .map(|c| get_out_val(c, 255))
+            .collect();
+        assert_eq!(png.samples, expected);
+    }
+
+    #[test]
+    fn png_export_writes_sixteen_bit_pixels() {
+        // Given
+        let exporter = PNGExporter::with_bit_depth(BitDepth::Sixteen);
+        let mut buffer = Vec::new();
+        let mut canvas = canvas::Canvas::new(3, 1);
+        canvas.write_pixel(0, 0, Color::new(1.5, 0.0, -0.5));
+        canvas.write_pixel(1, 0, Color::new(0.5, 0.25, 1.0));
+
+        // When
+        exporter.export(&canvas, &mut buffer).unwrap();
+
+        // Then
+        let png = decode_png(&buffer);
+        assert_eq!(png.bit_depth, 16);
+        assert_eq!(png.samples, vec![65535, 0, 0, 32768, 16384, 65535, 0, 0, 0]);
+    }
+
+    #[test]
+    fn png_export_compresses_uniform_canvas() {
+        // Given
+        let exporter = PNGExporter::new();
+        let mut buffer = Vec::new();
+        let canvas = canvas::Canvas::new(200, 100);
+
+        // When
+        exporter.export(&canvas, &mut buffer).unwrap();
+
+        // Then
+        assert!(buffer.len() < 1000, "{} bytes", buffer.len());
+        assert!(decode_png(&buffer).samples.iter().all(|s| *s == 0));
+    }
+
+    #[test]
+    fn png_export_rejects_empty_canvas() {
+        // Given
+        let exporter = PNGExporter::new();
+        let mut buffer = Vec::new();
+        let canvas = canvas::Canvas::new(0, 3);
+
+        // When
+        let result = exporter.export(&canvas, &mut buffer);
+
+        // Then
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
 }
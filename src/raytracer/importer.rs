@@ -0,0 +1,333 @@
+use std::{error::Error, fmt::Display, io::Read};
+
+use super::{canvas::Canvas, color::Color};
+
+#[derive(Debug)]
+pub enum PPMError {
+    Io(std::io::Error),
+    /// The data doesn't start with the magic number of a plain (P3) or binary (P6) PPM image.
+    UnsupportedFormat(String),
+    InvalidNumber(String),
+    InvalidHeader(String),
+    SampleOutOfRange { value: u32, max_value: u32 },
+    UnexpectedEnd,
+}
+
+impl Display for PPMError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PPMError::Io(err) => write!(f, "failed to read PPM data: {}", err),
+            PPMError::UnsupportedFormat(magic) => write!(f, "unsupported image format '{}', expected P3 or P6", magic),
+            PPMError::InvalidNumber(token) => write!(f, "expected a number, got '{}'", token),
+            PPMError::InvalidHeader(message) => write!(f, "invalid PPM header: {}", message),
+            PPMError::SampleOutOfRange { value, max_value } => write!(f, "sample {} is larger than the maximum value {}", value, max_value),
+            PPMError::UnexpectedEnd => write!(f, "unexpected end of PPM data"),
+        }
+    }
+}
+
+impl Error for PPMError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PPMError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for PPMError {
+    fn from(err: std::io::Error) -> Self {
+        PPMError::Io(err)
+    }
+}
+
+const MAX_PPM_VALUE: u32 = 65535;
+
+/// Reads a plain (P3) or binary (P6) PPM image, scaling its samples by the maximum value into colors between 0 and 1.
+pub fn canvas_from_ppm<R: Read>(mut reader: R) -> Result<Canvas, PPMError> {
+    let mut data = vec![];
+    reader.read_to_end(&mut data)?;
+    let mut tokens = Tokens { data: &data, position: 0 };
+
+    let magic = tokens.next().ok_or(PPMError::UnexpectedEnd)?;
+    let binary = match magic {
+        b"P3" => false,
+        b"P6" => true,
+        other => return Err(PPMError::UnsupportedFormat(String::from_utf8_lossy(other).into_owned())),
+    };
+
+    let width = tokens.number()? as usize;
+    let height = tokens.number()? as usize;
+    let max_value = tokens.number()?;
+    if width == 0 || height == 0 {
+        return Err(PPMError::InvalidHeader(format!("image size {}x{} is empty", width, height)));
+    }
+    if max_value == 0 || max_value > MAX_PPM_VALUE {
+        return Err(PPMError::InvalidHeader(format!("maximum value {} is not between 1 and {}", max_value, MAX_PPM_VALUE)));
+    }
+
+    if binary {
+        // exactly one whitespace character separates the header from the samples
+        tokens.position += 1;
+    }
+
+    // check the size against the data before allocating, so a bogus header can't request a huge canvas;
+    // binary samples take one or two bytes, plain ones at least one digit each
+    let bytes_per_sample = if binary && max_value > u8::MAX as u32 { 2 } else { 1 };
+    let required = width.checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(3 * bytes_per_sample))
+        .ok_or_else(|| PPMError::InvalidHeader(format!("image size {}x{} is too large", width, height)))?;
+    if data.len().saturating_sub(tokens.position) < required {
+        return Err(PPMError::UnexpectedEnd);
+    }
+
+    let mut canvas = Canvas::new(width, height);
+    let mut sample = || {
+        let value = if binary { tokens.binary_sample(max_value)? } else { tokens.number()? };
+        if value > max_value {
+            return Err(PPMError::SampleOutOfRange { value, max_value });
+        }
+        Ok(value as f64 / max_value as f64)
+    };
+    for y in 0..height {
+        for x in 0..width {
+            let (red, green, blue) = (sample()?, sample()?, sample()?);
+            canvas.write_pixel(x, y, Color::new(red, green, blue));
+        }
+    }
+    Ok(canvas)
+}
+
+// Splits the plain parts of a PPM image into whitespace separated tokens, skipping `#` comments.
+struct Tokens<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn next(&mut self) -> Option<&'a [u8]> {
+        loop {
+            match self.data.get(self.position)? {
+                b'#' => {
+                    while self.data.get(self.position).is_some_and(|b| *b != b'\n' && *b != b'\r') {
+                        self.position += 1;
+                    }
+                }
+                b if b.is_ascii_whitespace() => self.position += 1,
+                _ => break,
+            }
+        }
+
+        let start = self.position;
+        while self.data.get(self.position).is_some_and(|b| !b.is_ascii_whitespace() && *b != b'#') {
+            self.position += 1;
+        }
+        Some(&self.data[start..self.position])
+    }
+
+    fn number(&mut self) -> Result<u32, PPMError> {
+        let token = self.next().ok_or(PPMError::UnexpectedEnd)?;
+        std::str::from_utf8(token)
+            .ok()
+            .filter(|t| t.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|t| t.parse().ok())
+            .ok_or_else(|| PPMError::InvalidNumber(String::from_utf8_lossy(token).into_owned()))
+    }
+
+    // samples take two bytes, most significant first, if the maximum value doesn't fit into one
+    fn binary_sample(&mut self, max_value: u32) -> Result<u32, PPMError> {
+        let size = if max_value > u8::MAX as u32 { 2 } else { 1 };
+        let bytes = self.data.get(self.position..self.position + size).ok_or(PPMError::UnexpectedEnd)?;
+        self.position += size;
+        Ok(bytes.iter().fold(0, |value, byte| value << 8 | *byte as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::raytracer::exporter::{Exporter, PPMEncoding, PPMExporter};
+
+    use super::*;
+
+    #[test]
+    fn canvas_from_ppm_rejects_wrong_magic_number() {
+        // Given
+        let ppm = "P32\n1 1\n255\n0 0 0\n";
+
+        // When
+        let result = canvas_from_ppm(ppm.as_bytes());
+
+        // Then
+        assert!(matches!(result, Err(PPMError::UnsupportedFormat(magic)) if magic == "P32"));
+    }
+
+    #[test]
+    fn canvas_from_ppm_reads_size() {
+        // Given
+        let ppm = "P3\n10 2\n255\n".to_string() + &"0 0 0\n".repeat(20);
+
+        // When
+        let canvas = canvas_from_ppm(ppm.as_bytes()).unwrap();
+
+        // Then
+        assert_eq!(canvas.width(), 10);
+        assert_eq!(canvas.height(), 2);
+    }
+
+    #[test]
+    fn canvas_from_ppm_reads_pixels() {
+        // Given
+        let ppm = "P3
+4 3
+255
+255 127 0  0 127 255  127 255 0  255 255 255
+0 0 0  255 0 0  0 255 0  0 0 255
+255 255 0  0 255 255  255 0 255  127 127 127
+";
+
+        // When
+        let canvas = canvas_from_ppm(ppm.as_bytes()).unwrap();
+
+        // Then
+        let cases = [
+            (0, 0, Color::new(1.0, 0.49804, 0.0)),
+            (1, 0, Color::new(0.0, 0.49804, 1.0)),
+            (2, 0, Color::new(0.49804, 1.0, 0.0)),
+            (3, 0, Color::new(1.0, 1.0, 1.0)),
+            (0, 1, Color::new(0.0, 0.0, 0.0)),
+            (1, 1, Color::new(1.0, 0.0, 0.0)),
+            (2, 1, Color::new(0.0, 1.0, 0.0)),
+            (3, 1, Color::new(0.0, 0.0, 1.0)),
+            (0, 2, Color::new(1.0, 1.0, 0.0)),
+            (1, 2, Color::new(0.0, 1.0, 1.0)),
+            (2, 2, Color::new(1.0, 0.0, 1.0)),
+            (3, 2, Color::new(0.49804, 0.49804, 0.49804)),
+        ];
+        for (x, y, color) in cases {
+            assert_eq!(canvas.pixel_at(x, y), color, "pixel {}, {}", x, y);
+        }
+    }
+
+    #[test]
+    fn canvas_from_ppm_skips_comments() {
+        // Given
+        let ppm = "P3
+# this is a comment
+2 1 # the size
+# this, too
+255
+255 255 255#one pixel
+# another comment
+255 0 255
+";
+
+        // When
+        let canvas = canvas_from_ppm(ppm.as_bytes()).unwrap();
+
+        // Then
+        assert_eq!(canvas.pixel_at(0, 0), Color::new(1.0, 1.0, 1.0));
+        assert_eq!(canvas.pixel_at(1, 0), Color::new(1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn canvas_from_ppm_allows_pixels_to_span_lines() {
+        // Given
+        let ppm = "P3\r\n1   1\n255\n51\n\t153\n\n204\n";
+
+        // When
+        let canvas = canvas_from_ppm(ppm.as_bytes()).unwrap();
+
+        // Then
+        assert_eq!(canvas.pixel_at(0, 0), Color::new(0.2, 0.6, 0.8));
+    }
+
+    #[test]
+    fn canvas_from_ppm_scales_by_max_value() {
+        // Given
+        let ppm = "P3\n2 2\n100\n100 100 100  50 50 50\n75 50 25  0 0 0\n";
+
+        // When
+        let canvas = canvas_from_ppm(ppm.as_bytes()).unwrap();
+
+        // Then
+        assert_eq!(canvas.pixel_at(0, 1), Color::new(0.75, 0.5, 0.25));
+    }
+
+    #[test]
+    fn canvas_from_ppm_reads_binary_pixels() {
+        // Given
+        let mut ppm = b"P6\n# binary\n2 1\n255\n".to_vec();
+        ppm.extend_from_slice(&[255, 51, 0, 10, 32, 35]);
+
+        // When
+        let canvas = canvas_from_ppm(ppm.as_slice()).unwrap();
+
+        // Then
+        assert_eq!(canvas.pixel_at(0, 0), Color::new(1.0, 0.2, 0.0));
+        assert_eq!(canvas.pixel_at(1, 0), Color::new(10.0 / 255.0, 32.0 / 255.0, 35.0 / 255.0));
+    }
+
+    #[test]
+    fn canvas_from_ppm_reads_sixteen_bit_binary_pixels() {
+        // Given
+        let mut ppm = b"P6 1 1 1000\n".to_vec();
+        ppm.extend_from_slice(&[0x03, 0xe8, 0x01, 0xf4, 0x00, 0x00]);
+
+        // When
+        let canvas = canvas_from_ppm(ppm.as_slice()).unwrap();
+
+        // Then
+        assert_eq!(canvas.pixel_at(0, 0), Color::new(1.0, 0.5, 0.0));
+    }
+
+    #[test]
+    fn canvas_from_ppm_reads_exported_images() {
+        // Given
+        let mut canvas = Canvas::new(3, 2);
+        canvas.write_pixel(0, 0, Color::new(1.0, 0.5, 0.0));
+        canvas.write_pixel(2, 1, Color::new(0.25, 0.75, 1.0));
+
+        for (encoding, max_value) in [(PPMEncoding::Ascii, 255), (PPMEncoding::Binary, 255), (PPMEncoding::Binary, 65535)] {
+            let mut ppm = Vec::new();
+            PPMExporter::with_options(encoding, max_value).export(&canvas, &mut ppm).unwrap();
+
+            // When
+            let imported = canvas_from_ppm(ppm.as_slice()).unwrap();
+
+            // Then
+            assert_eq!(imported.width(), 3);
+            assert_eq!(imported.height(), 2);
+            for (expected, actual) in canvas.pixels().iter().zip(imported.pixels()) {
+                for (e, a) in [(expected.red(), actual.red()), (expected.green(), actual.green()), (expected.blue(), actual.blue())] {
+                    assert!((e - a).abs() <= 0.5 / max_value as f64, "{:?}: {} != {}", encoding, e, a);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn canvas_from_ppm_rejects_malformed_input() {
+        // Given
+        type Case = (&'static [u8], fn(&PPMError) -> bool);
+        let cases: [Case; 10] = [
+            (b"P3\n4294967295 4294967295\n255\n0 0 0\n", |e| matches!(e, PPMError::InvalidHeader(_))),
+            (b"P6\n100000 100000\n255\n\x00\x00\x00", |e| matches!(e, PPMError::UnexpectedEnd)),
+            (b"", |e| matches!(e, PPMError::UnexpectedEnd)),
+            (b"P3\n2 1\n255\n0 0 0 0 0\n", |e| matches!(e, PPMError::UnexpectedEnd)),
+            (b"P6\n1 1\n255\n\x00\x00", |e| matches!(e, PPMError::UnexpectedEnd)),
+            (b"P3\n2 x\n255\n", |e| matches!(e, PPMError::InvalidNumber(t) if t == "x")),
+            (b"P3\n1 1\n255\n0 -1 0\n", |e| matches!(e, PPMError::InvalidNumber(t) if t == "-1")),
+            (b"P3\n0 1\n255\n", |e| matches!(e, PPMError::InvalidHeader(_))),
+            (b"P3\n1 1\n65536\n0 0 0\n", |e| matches!(e, PPMError::InvalidHeader(_))),
+            (b"P3\n1 1\n100\n0 101 0\n", |e| matches!(e, PPMError::SampleOutOfRange { value: 101, max_value: 100 })),
+        ];
+
+        for (ppm, is_expected) in cases {
+            // When
+            let err = canvas_from_ppm(ppm).err().unwrap();
+
+            // Then
+            assert!(is_expected(&err), "{:?} for {:?}", err, String::from_utf8_lossy(ppm));
+        }
+    }
+}
@@ -52,8 +52,7 @@ impl Matrix {
             .filter(|(i, _)| i / self.width != row && i % self.width != column)
             .map(|(_, v)| *v)
             .collect();
-        let out = Matrix::from_values(self.height - 1, self.width - 1, values);
-        out
+        Matrix::from_values(self.height - 1, self.width - 1, values)
     }
 
     pub fn minor(&self, row: usize, column: usize) -> f64 {
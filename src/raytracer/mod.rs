@@ -3,6 +3,16 @@ pub mod matrix;
 pub mod color;
 pub mod canvas;
 pub mod exporter;
+pub mod importer;
+pub mod deflate;
 pub mod transformation;
 pub mod objects;
 pub mod ray;
+pub mod world;
+pub mod camera;
+pub mod random;
+pub mod obj_parser;
+pub mod scene;
+
+/// Tolerance used to nudge points off surfaces and to treat nearly equal values as equal.
+pub const EPSILON: f64 = 0.00001;
@@ -0,0 +1,486 @@
+use std::{error::Error, fmt::Display, io::BufRead};
+
+use super::{objects::{group::Group, object::Object, smooth_triangle::SmoothTriangle, triangle::Triangle}, tuple::Tuple};
+
+/// The group faces end up in until the first `g` or `o` statement.
+pub const DEFAULT_GROUP: &str = "";
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl Display for ObjError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjError::Io(err) => write!(f, "failed to read OBJ data: {}", err),
+            ObjError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for ObjError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ObjError::Io(err) => Some(err),
+            ObjError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ObjError {
+    fn from(err: std::io::Error) -> Self {
+        ObjError::Io(err)
+    }
+}
+
+/// A corner of a face, holding zero-based indices into the vertex, texture and normal lists.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaceVertex {
+    vertex: usize,
+    texture: Option<usize>,
+    normal: Option<usize>,
+}
+
+impl FaceVertex {
+    pub fn vertex(&self) -> usize {
+        self.vertex
+    }
+
+    pub fn texture(&self) -> Option<usize> {
+        self.texture
+    }
+
+    pub fn normal(&self) -> Option<usize> {
+        self.normal
+    }
+}
+
+pub struct ObjGroup {
+    name: String,
+    triangles: Vec<[FaceVertex; 3]>,
+}
+
+impl ObjGroup {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn triangles(&self) -> &Vec<[FaceVertex; 3]> {
+        &self.triangles
+    }
+}
+
+/// The contents of a Wavefront OBJ file, with every polygon already split into triangles.
+pub struct ObjFile {
+    vertices: Vec<Tuple>,
+    normals: Vec<Tuple>,
+    texture_coords: Vec<(f64, f64)>,
+    groups: Vec<ObjGroup>,
+    ignored_lines: usize,
+}
+
+impl ObjFile {
+    pub fn vertices(&self) -> &Vec<Tuple> {
+        &self.vertices
+    }
+
+    pub fn normals(&self) -> &Vec<Tuple> {
+        &self.normals
+    }
+
+    pub fn texture_coords(&self) -> &Vec<(f64, f64)> {
+        &self.texture_coords
+    }
+
+    pub fn groups(&self) -> &Vec<ObjGroup> {
+        &self.groups
+    }
+
+    pub fn default_group(&self) -> &ObjGroup {
+        &self.groups[0]
+    }
+
+    pub fn group(&self, name: &str) -> Option<&ObjGroup> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    /// The number of statements the parser did not understand, blank lines and comments are not counted.
+    pub fn ignored_lines(&self) -> usize {
+        self.ignored_lines
+    }
+
+    /// Builds a group holding the triangles of the default group and one child group per named group.
+    /// Faces with a normal on every corner become smooth triangles.
+    pub fn to_group(&self) -> Group {
+        let mut result = Group::default();
+        for obj_group in self.groups.iter() {
+            let triangles = obj_group.triangles.iter().map(|t| self.triangle(t));
+            if obj_group.name == DEFAULT_GROUP {
+                for triangle in triangles {
+                    result.add_child(triangle);
+                }
+            } else if !obj_group.triangles.is_empty() {
+                result.add_child(Box::new(Group::new(triangles.collect())));
+            }
+        }
+        result
+    }
+
+    fn triangle(&self, corners: &[FaceVertex; 3]) -> Box<dyn Object> {
+        let [p1, p2, p3] = corners.map(|c| self.vertices[c.vertex].clone());
+        match corners.map(|c| c.normal) {
+            [Some(n1), Some(n2), Some(n3)] => Box::new(SmoothTriangle::new(
+                p1, p2, p3,
+                self.normals[n1].clone(), self.normals[n2].clone(), self.normals[n3].clone(),
+            )),
+            _ => Box::new(Triangle::new(p1, p2, p3)),
+        }
+    }
+}
+
+/// Reads OBJ data, failing on malformed numbers and on faces that refer to vertices not yet defined.
+pub fn parse_obj<R: BufRead>(reader: R) -> Result<ObjFile, ObjError> {
+    let mut obj = ObjFile {
+        vertices: vec![],
+        normals: vec![],
+        texture_coords: vec![],
+        groups: vec![ObjGroup { name: DEFAULT_GROUP.to_string(), triangles: vec![] }],
+        ignored_lines: 0,
+    };
+    let mut current_group = 0;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = index + 1;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        match tokens.split_first() {
+            None => {}
+            Some((keyword, _)) if keyword.starts_with('#') => {}
+            Some((&"v", args)) => {
+                let [x, y, z] = parse_floats(args, line_number)?;
+                obj.vertices.push(Tuple::point(x, y, z));
+            }
+            Some((&"vn", args)) => {
+                let [x, y, z] = parse_floats(args, line_number)?;
+                obj.normals.push(Tuple::vector(x, y, z));
+            }
+            Some((&"vt", args)) => {
+                // v defaults to 0 for 1D textures and the w of 3D textures is ignored
+                let [u] = parse_floats(args, line_number)?;
+                let [v] = match args.len() {
+                    1 => [0.0],
+                    _ => parse_floats(&args[1..], line_number)?,
+                };
+                obj.texture_coords.push((u, v));
+            }
+            Some((&"f", args)) => {
+                let corners = args.iter()
+                    .map(|t| parse_face_vertex(t, &obj, line_number))
+                    .collect::<Result<Vec<FaceVertex>, ObjError>>()?;
+                if corners.len() < 3 {
+                    return Err(parse_error(line_number, "a face needs at least 3 vertices"));
+                }
+                // convex polygons are split into a fan of triangles around the first vertex
+                for i in 1..corners.len() - 1 {
+                    obj.groups[current_group].triangles.push([corners[0], corners[i], corners[i + 1]]);
+                }
+            }
+            Some((&"g", args)) | Some((&"o", args)) => {
+                let name = args.join(" ");
+                current_group = match obj.groups.iter().position(|g| g.name == name) {
+                    Some(existing) => existing,
+                    None => {
+                        obj.groups.push(ObjGroup { name, triangles: vec![] });
+                        obj.groups.len() - 1
+                    }
+                };
+            }
+            Some(_) => obj.ignored_lines += 1,
+        }
+    }
+
+    Ok(obj)
+}
+
+fn parse_error(line: usize, message: &str) -> ObjError {
+    ObjError::Parse { line, message: message.to_string() }
+}
+
+// Parses the leading N numbers of a statement, any further optional components (like `w`) are ignored.
+fn parse_floats<const N: usize>(args: &[&str], line: usize) -> Result<[f64; N], ObjError> {
+    if args.len() < N {
+        return Err(parse_error(line, &format!("expected {} numbers, found {}", N, args.len())));
+    }
+    let mut values = [0.0; N];
+    for (value, arg) in values.iter_mut().zip(args) {
+        *value = arg.parse().map_err(|_| parse_error(line, &format!("invalid number '{}'", arg)))?;
+    }
+    Ok(values)
+}
+
+// Parses a face corner in the `v`, `v/vt`, `v//vn` or `v/vt/vn` form.
+fn parse_face_vertex(token: &str, obj: &ObjFile, line: usize) -> Result<FaceVertex, ObjError> {
+    let mut parts = token.split('/');
+    let vertex = resolve_index(parts.next(), obj.vertices.len(), line)?
+        .ok_or_else(|| parse_error(line, &format!("face vertex '{}' has no vertex index", token)))?;
+    let texture = resolve_index(parts.next(), obj.texture_coords.len(), line)?;
+    let normal = resolve_index(parts.next(), obj.normals.len(), line)?;
+    Ok(FaceVertex { vertex, texture, normal })
+}
+
+// Turns a one-based (or negative, counting back from the end) OBJ index into a zero-based index.
+fn resolve_index(part: Option<&str>, count: usize, line: usize) -> Result<Option<usize>, ObjError> {
+    let part = match part {
+        None | Some("") => return Ok(None),
+        Some(part) => part,
+    };
+    let index: i64 = part.parse().map_err(|_| parse_error(line, &format!("invalid index '{}'", part)))?;
+    let resolved = if index > 0 { index - 1 } else { count as i64 + index };
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(parse_error(line, &format!("index {} is out of range", index)));
+    }
+    Ok(Some(resolved as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::raytracer::ray::Ray;
+
+    use super::*;
+
+    fn parse(data: &str) -> ObjFile {
+        parse_obj(data.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn parse_obj_ignores_unrecognized_lines() {
+        // Given
+        let data = "There was a young lady named Bright\n\
+                    who traveled much faster than light.\n\
+                    She set out one day\n\
+                    in a relative way,\n\
+                    and came back the previous night.\n";
+
+        // When
+        let obj = parse(data);
+
+        // Then
+        assert_eq!(obj.ignored_lines(), 5);
+    }
+
+    #[test]
+    fn parse_obj_skips_comments_and_blank_lines() {
+        // Given
+        let data = "# a comment\n\n   \nv 1 2 3\n";
+
+        // When
+        let obj = parse(data);
+
+        // Then
+        assert_eq!(obj.ignored_lines(), 0);
+        assert_eq!(obj.vertices().len(), 1);
+    }
+
+    #[test]
+    fn parse_obj_reads_vertices() {
+        // Given
+        let data = "v -1 1 0\nv -1.0000 0.5000 0.0000\nv 1 0 0\nv 1 1 0\n";
+
+        // When
+        let obj = parse(data);
+
+        // Then
+        assert_eq!(obj.vertices(), &vec![
+            Tuple::point(-1.0, 1.0, 0.0),
+            Tuple::point(-1.0, 0.5, 0.0),
+            Tuple::point(1.0, 0.0, 0.0),
+            Tuple::point(1.0, 1.0, 0.0),
+        ]);
+    }
+
+    #[test]
+    fn parse_obj_reads_normals_and_texture_coords() {
+        // Given
+        let data = "vn 0 0 1\nvn 0.707 0 -0.707\nvn 1 2 3\nvt 0.5 0.25\n";
+
+        // When
+        let obj = parse(data);
+
+        // Then
+        assert_eq!(obj.normals(), &vec![
+            Tuple::vector(0.0, 0.0, 1.0),
+            Tuple::vector(0.707, 0.0, -0.707),
+            Tuple::vector(1.0, 2.0, 3.0),
+        ]);
+        assert_eq!(obj.texture_coords(), &vec![(0.5, 0.25)]);
+    }
+
+    #[test]
+    fn parse_obj_defaults_missing_texture_v_to_zero() {
+        // Given
+        let data = "vt 0.5
+";
+
+        // When
+        let obj = parse(data);
+
+        // Then
+        assert_eq!(obj.texture_coords(), &vec![(0.5, 0.0)]);
+    }
+
+    #[test]
+    fn parse_obj_ignores_texture_w() {
+        // Given
+        let data = "vt 0.5 0.25 1
+";
+
+        // When
+        let obj = parse(data);
+
+        // Then
+        assert_eq!(obj.texture_coords(), &vec![(0.5, 0.25)]);
+    }
+
+    #[test]
+    fn parse_obj_reads_triangle_faces() {
+        // Given
+        let data = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\nf 1 2 3\nf 1 3 4\n";
+
+        // When
+        let obj = parse(data);
+
+        // Then
+        let triangles = obj.default_group().triangles();
+        assert_eq!(triangles.len(), 2);
+        assert_eq!(triangles[0].map(|c| c.vertex()), [0, 1, 2]);
+        assert_eq!(triangles[1].map(|c| c.vertex()), [0, 2, 3]);
+    }
+
+    #[test]
+    fn parse_obj_triangulates_polygons() {
+        // Given
+        let data = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nv 0 2 0\n\nf 1 2 3 4 5\n";
+
+        // When
+        let obj = parse(data);
+
+        // Then
+        let triangles = obj.default_group().triangles();
+        assert_eq!(triangles.len(), 3);
+        assert_eq!(triangles[0].map(|c| c.vertex()), [0, 1, 2]);
+        assert_eq!(triangles[1].map(|c| c.vertex()), [0, 2, 3]);
+        assert_eq!(triangles[2].map(|c| c.vertex()), [0, 3, 4]);
+    }
+
+    #[test]
+    fn parse_obj_reads_faces_with_texture_and_normal_indices() {
+        // Given
+        let data = "v 0 1 0\nv -1 0 0\nv 1 0 0\nvt 0 0\nvn -1 0 0\nvn 1 0 0\nvn 0 1 0\n\nf 1//3 2//1 3//2\nf 1/1/3 2/1/1 3/1/2\n";
+
+        // When
+        let obj = parse(data);
+
+        // Then
+        let triangles = obj.default_group().triangles();
+        assert_eq!(triangles[0], triangles[1].map(|c| FaceVertex { texture: None, ..c }));
+        assert_eq!(triangles[0].map(|c| c.normal()), [Some(2), Some(0), Some(1)]);
+        assert_eq!(triangles[1].map(|c| c.texture()), [Some(0), Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn parse_obj_rejects_zero_texture_index() {
+        // Given
+        let data = "v 0 1 0\nv -1 0 0\nv 1 0 0\nvt 0 0\nf 1/1 2/0 3/1\n";
+
+        // When
+        let result = parse_obj(data.as_bytes());
+
+        // Then
+        match result {
+            Err(ObjError::Parse { line, .. }) => assert_eq!(line, 5),
+            _ => panic!("expected the zero texture index to be rejected"),
+        }
+    }
+
+    #[test]
+    fn parse_obj_resolves_negative_indices() {
+        // Given
+        let data = "v -1 1 0\nv -1 0 0\nv 1 0 0\nf -3 -2 -1\n";
+
+        // When
+        let obj = parse(data);
+
+        // Then
+        assert_eq!(obj.default_group().triangles()[0].map(|c| c.vertex()), [0, 1, 2]);
+    }
+
+    #[test]
+    fn parse_obj_puts_faces_in_named_groups() {
+        // Given
+        let data = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\ng FirstGroup\nf 1 2 3\no SecondGroup\nf 1 3 4\n";
+
+        // When
+        let obj = parse(data);
+
+        // Then
+        assert!(obj.default_group().triangles().is_empty());
+        assert_eq!(obj.group("FirstGroup").unwrap().triangles()[0].map(|c| c.vertex()), [0, 1, 2]);
+        assert_eq!(obj.group("SecondGroup").unwrap().triangles()[0].map(|c| c.vertex()), [0, 2, 3]);
+    }
+
+    #[test]
+    fn parse_obj_reports_line_of_bad_statement() {
+        // Given
+        let cases = [
+            ("v 1 2 3\nv 1 two 3\n", 2),
+            ("v 1 2\n", 1),
+            ("v 1 2 3\nv 1 2 3\n\nf 1 2\n", 4),
+            ("v 1 2 3\nf 1 2 3\n", 2),
+        ];
+
+        for (data, expected_line) in cases {
+            // When
+            let result = parse_obj(data.as_bytes());
+
+            // Then
+            match result {
+                Err(ObjError::Parse { line, .. }) => assert_eq!(line, expected_line),
+                _ => panic!("expected a parse error for {:?}", data),
+            }
+        }
+    }
+
+    #[test]
+    fn to_group_converts_named_groups() {
+        // Given
+        let data = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\nf 1 2 3\ng FirstGroup\nf 1 2 3\ng SecondGroup\nf 1 3 4\n";
+        let obj = parse(data);
+
+        // When
+        let g = obj.to_group();
+
+        // Then
+        assert_eq!(g.children().len(), 3);
+        let r = Ray::new(Tuple::point(-0.5, 0.5, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(g.intersects(&r).len(), 2);
+    }
+
+    #[test]
+    fn to_group_creates_smooth_triangles_from_normals() {
+        // Given
+        let data = "v 0 1 0\nv -1 0 0\nv 1 0 0\nvn 0 1 0\nvn -1 0 0\nvn 1 0 0\nf 1//1 2//2 3//3\n";
+        let obj = parse(data);
+        let g = obj.to_group();
+        let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = g.intersects(&r);
+
+        // When
+        let n = xs[0].object().normal_at_hit(&Tuple::point(-0.2, 0.3, 0.0), &xs[0]);
+
+        // Then
+        assert_eq!(n, Tuple::vector(-0.5547, 0.83205, 0.0));
+    }
+}
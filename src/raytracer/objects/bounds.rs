@@ -0,0 +1,299 @@
+use crate::raytracer::{matrix::Matrix, ray::Ray, tuple::Tuple, EPSILON};
+
+/// An axis-aligned bounding box, used to skip objects a ray can't possibly hit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundingBox {
+    min: Tuple,
+    max: Tuple,
+}
+
+impl BoundingBox {
+    pub fn new(min: Tuple, max: Tuple) -> BoundingBox {
+        Self { min, max }
+    }
+
+    /// A box that contains nothing, adding a point to it yields a box around just that point.
+    pub fn empty() -> BoundingBox {
+        Self::new(
+            Tuple::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            Tuple::point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        )
+    }
+
+    /// A box that contains everything, used for unbounded shapes like planes.
+    pub fn infinite() -> BoundingBox {
+        Self::new(
+            Tuple::point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            Tuple::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        )
+    }
+
+    pub fn min(&self) -> &Tuple {
+        &self.min
+    }
+
+    pub fn max(&self) -> &Tuple {
+        &self.max
+    }
+
+    pub fn is_finite(&self) -> bool {
+        [self.min.x(), self.min.y(), self.min.z(), self.max.x(), self.max.y(), self.max.z()].iter().all(|c| c.is_finite())
+    }
+
+    /// Whether the box contains nothing, like a group without children.
+    pub fn is_empty(&self) -> bool {
+        self.min.x() > self.max.x() || self.min.y() > self.max.y() || self.min.z() > self.max.z()
+    }
+
+    pub fn add_point(&mut self, point: &Tuple) {
+        self.min = Tuple::point(self.min.x().min(point.x()), self.min.y().min(point.y()), self.min.z().min(point.z()));
+        self.max = Tuple::point(self.max.x().max(point.x()), self.max.y().max(point.y()), self.max.z().max(point.z()));
+    }
+
+    /// Grows the box so it also contains `other`.
+    pub fn merge(&mut self, other: &BoundingBox) {
+        // the corners of an empty box lie at infinity, adding them would make the box infinite
+        if other.is_empty() {
+            return;
+        }
+        self.add_point(&other.min);
+        self.add_point(&other.max);
+    }
+
+    pub fn contains_point(&self, point: &Tuple) -> bool {
+        self.min.x() <= point.x() && point.x() <= self.max.x()
+            && self.min.y() <= point.y() && point.y() <= self.max.y()
+            && self.min.z() <= point.z() && point.z() <= self.max.z()
+    }
+
+    pub fn contains_box(&self, other: &BoundingBox) -> bool {
+        self.contains_point(&other.min) && self.contains_point(&other.max)
+    }
+
+    /// Returns the axis-aligned box around all eight corners of this box after transforming them.
+    /// Empty boxes stay empty and boxes that are infinite on any axis stay infinite, as their corners can't be transformed.
+    pub fn transform(&self, transform: &Matrix) -> BoundingBox {
+        if self.is_empty() {
+            return Self::empty();
+        }
+        if !self.is_finite() {
+            return Self::infinite();
+        }
+
+        let mut result = Self::empty();
+        for x in [self.min.x(), self.max.x()] {
+            for y in [self.min.y(), self.max.y()] {
+                for z in [self.min.z(), self.max.z()] {
+                    result.add_point(&(transform * &Tuple::point(x, y, z)));
+                }
+            }
+        }
+        result
+    }
+
+    pub fn intersects(&self, ray: &Ray) -> bool {
+        // the slabs of an empty box are inverted, which the slab test would mistake for an infinite box
+        if self.is_empty() {
+            return false;
+        }
+
+        let (xtmin, xtmax) = check_axis(ray.origin().x(), ray.direction().x(), self.min.x(), self.max.x());
+        let (ytmin, ytmax) = check_axis(ray.origin().y(), ray.direction().y(), self.min.y(), self.max.y());
+        let (ztmin, ztmax) = check_axis(ray.origin().z(), ray.direction().z(), self.min.z(), self.max.z());
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        tmin <= tmax
+    }
+
+    /// Splits the box in half along its longest axis.
+    pub fn split(&self) -> (BoundingBox, BoundingBox) {
+        let dx = self.max.x() - self.min.x();
+        let dy = self.max.y() - self.min.y();
+        let dz = self.max.z() - self.min.z();
+        let greatest = dx.max(dy).max(dz);
+
+        let (mut x0, mut y0, mut z0) = (self.min.x(), self.min.y(), self.min.z());
+        let (mut x1, mut y1, mut z1) = (self.max.x(), self.max.y(), self.max.z());
+
+        if greatest == dx {
+            x0 += dx / 2.0;
+            x1 = x0;
+        } else if greatest == dy {
+            y0 += dy / 2.0;
+            y1 = y0;
+        } else {
+            z0 += dz / 2.0;
+            z1 = z0;
+        }
+
+        let left = Self::new(self.min.clone(), Tuple::point(x1, y1, z1));
+        let right = Self::new(Tuple::point(x0, y0, z0), self.max.clone());
+        (left, right)
+    }
+}
+
+/// Returns where the ray crosses the two planes of a slab between `min` and `max` on a single axis.
+pub(crate) fn check_axis(origin: f64, direction: f64, min: f64, max: f64) -> (f64, f64) {
+    let tmin_numerator = min - origin;
+    let tmax_numerator = max - origin;
+
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (tmin_numerator * f64::INFINITY, tmax_numerator * f64::INFINITY)
+    };
+
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{PI, SQRT_2};
+
+    use crate::raytracer::transformation;
+
+    use super::*;
+
+    #[test]
+    fn add_point_grows_empty_box() {
+        // Given
+        let mut b = BoundingBox::empty();
+
+        // When
+        b.add_point(&Tuple::point(-5.0, 2.0, 0.0));
+        b.add_point(&Tuple::point(7.0, 0.0, -3.0));
+
+        // Then
+        assert_eq!(b.min(), &Tuple::point(-5.0, 0.0, -3.0));
+        assert_eq!(b.max(), &Tuple::point(7.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn merge_adds_other_box() {
+        // Given
+        let mut b1 = BoundingBox::new(Tuple::point(-5.0, -2.0, 0.0), Tuple::point(7.0, 4.0, 4.0));
+        let b2 = BoundingBox::new(Tuple::point(8.0, -7.0, -2.0), Tuple::point(14.0, 2.0, 8.0));
+
+        // When
+        b1.merge(&b2);
+
+        // Then
+        assert_eq!(b1.min(), &Tuple::point(-5.0, -7.0, -2.0));
+        assert_eq!(b1.max(), &Tuple::point(14.0, 4.0, 8.0));
+    }
+
+    #[test]
+    fn contains_point_and_box() {
+        // Given
+        let b = BoundingBox::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+
+        // When & Then
+        assert!(b.contains_point(&Tuple::point(5.0, -2.0, 0.0)));
+        assert!(b.contains_point(&Tuple::point(8.0, 1.0, 3.0)));
+        assert!(!b.contains_point(&Tuple::point(3.0, 0.0, 3.0)));
+        assert!(!b.contains_point(&Tuple::point(8.0, 1.0, 8.0)));
+        assert!(b.contains_box(&BoundingBox::new(Tuple::point(6.0, -1.0, 1.0), Tuple::point(10.0, 3.0, 6.0))));
+        assert!(!b.contains_box(&BoundingBox::new(Tuple::point(4.0, -3.0, -1.0), Tuple::point(10.0, 3.0, 6.0))));
+    }
+
+    #[test]
+    fn transform_rotated_box() {
+        // Given
+        let b = BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0));
+        let m = &transformation::rotation_x(PI / 4.0) * &transformation::rotation_y(PI / 4.0);
+
+        // When
+        let b2 = b.transform(&m);
+
+        // Then
+        assert_eq!(b2.min(), &Tuple::point(-SQRT_2, -1.70711, -1.70711));
+        assert_eq!(b2.max(), &Tuple::point(SQRT_2, 1.70711, 1.70711));
+    }
+
+    #[test]
+    fn transform_keeps_infinite_box_infinite() {
+        // Given
+        let b = BoundingBox::new(Tuple::point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY), Tuple::point(f64::INFINITY, 0.0, f64::INFINITY));
+
+        // When
+        let b2 = b.transform(&transformation::rotation_x(PI / 4.0));
+
+        // Then
+        assert_eq!(b2, BoundingBox::infinite());
+    }
+
+    #[test]
+    fn transform_keeps_empty_box_empty() {
+        // When
+        let b = BoundingBox::empty().transform(&transformation::translation(1.0, 2.0, 3.0));
+
+        // Then
+        assert!(b.is_empty());
+        assert!(!b.intersects(&Ray::new(Tuple::point(1.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0))));
+    }
+
+    #[test]
+    fn merge_ignores_empty_box() {
+        // Given
+        let mut b = BoundingBox::new(Tuple::point(-1.0, -2.0, -3.0), Tuple::point(1.0, 2.0, 3.0));
+
+        // When
+        b.merge(&BoundingBox::empty());
+
+        // Then
+        assert_eq!(b, BoundingBox::new(Tuple::point(-1.0, -2.0, -3.0), Tuple::point(1.0, 2.0, 3.0)));
+    }
+
+    #[test]
+    fn intersects_ray() {
+        // Given
+        let b = BoundingBox::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+        let cases = [
+            (Tuple::point(15.0, 1.0, 2.0), Tuple::vector(-1.0, 0.0, 0.0), true),
+            (Tuple::point(-5.0, -1.0, 4.0), Tuple::vector(1.0, 0.0, 0.0), true),
+            (Tuple::point(7.0, 6.0, 5.0), Tuple::vector(0.0, -1.0, 0.0), true),
+            (Tuple::point(9.0, -5.0, 6.0), Tuple::vector(0.0, 1.0, 0.0), true),
+            (Tuple::point(8.0, 2.0, 12.0), Tuple::vector(0.0, 0.0, -1.0), true),
+            (Tuple::point(6.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), true),
+            (Tuple::point(8.0, 1.0, 3.5), Tuple::vector(0.0, 0.0, 1.0), true),
+            (Tuple::point(9.0, -1.0, -8.0), Tuple::vector(2.0, 4.0, 6.0), false),
+            (Tuple::point(8.0, 3.0, -4.0), Tuple::vector(6.0, 2.0, 4.0), false),
+            (Tuple::point(9.0, -1.0, -2.0), Tuple::vector(4.0, 6.0, 2.0), false),
+            (Tuple::point(4.0, 0.0, 9.0), Tuple::vector(0.0, 0.0, -1.0), false),
+            (Tuple::point(8.0, 6.0, -1.0), Tuple::vector(0.0, -1.0, 0.0), false),
+            (Tuple::point(12.0, 5.0, 4.0), Tuple::vector(-1.0, 0.0, 0.0), false),
+        ];
+
+        for (origin, direction, expected) in cases {
+            // When & Then
+            assert_eq!(b.intersects(&Ray::new(origin, direction.normalize())), expected);
+        }
+    }
+
+    #[test]
+    fn split_along_longest_axis() {
+        // Given
+        let cases = [
+            (Tuple::point(-1.0, -4.0, -5.0), Tuple::point(9.0, 6.0, 5.0), Tuple::point(4.0, 6.0, 5.0), Tuple::point(4.0, -4.0, -5.0)),
+            (Tuple::point(-1.0, -2.0, -3.0), Tuple::point(9.0, 5.5, 3.0), Tuple::point(4.0, 5.5, 3.0), Tuple::point(4.0, -2.0, -3.0)),
+            (Tuple::point(-1.0, -2.0, -3.0), Tuple::point(5.0, 8.0, 3.0), Tuple::point(5.0, 3.0, 3.0), Tuple::point(-1.0, 3.0, -3.0)),
+            (Tuple::point(-1.0, -2.0, -3.0), Tuple::point(5.0, 3.0, 7.0), Tuple::point(5.0, 3.0, 2.0), Tuple::point(-1.0, -2.0, 2.0)),
+        ];
+
+        for (min, max, left_max, right_min) in cases {
+            let b = BoundingBox::new(min.clone(), max.clone());
+
+            // When
+            let (left, right) = b.split();
+
+            // Then
+            assert_eq!(left, BoundingBox::new(min, left_max));
+            assert_eq!(right, BoundingBox::new(right_min, max));
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use crate::raytracer::{ray::Ray, tuple::Tuple, EPSILON};
+
+use super::{intersection::Intersection, object::Object};
+
+pub struct Computations<'a> {
+    t: f64,
+    object: &'a dyn Object,
+    point: Tuple,
+    eyev: Tuple,
+    normalv: Tuple,
+    inside: bool,
+    over_point: Tuple,
+    under_point: Tuple,
+    reflectv: Tuple,
+    n1: f64,
+    n2: f64,
+}
+
+impl<'a> Computations<'a> {
+    pub fn t(&self) -> f64 {
+        self.t
+    }
+
+    pub fn object(&self) -> &'a dyn Object {
+        self.object
+    }
+
+    pub fn point(&self) -> &Tuple {
+        &self.point
+    }
+
+    pub fn eyev(&self) -> &Tuple {
+        &self.eyev
+    }
+
+    pub fn normalv(&self) -> &Tuple {
+        &self.normalv
+    }
+
+    pub fn inside(&self) -> bool {
+        self.inside
+    }
+
+    pub fn over_point(&self) -> &Tuple {
+        &self.over_point
+    }
+
+    pub fn under_point(&self) -> &Tuple {
+        &self.under_point
+    }
+
+    pub fn reflectv(&self) -> &Tuple {
+        &self.reflectv
+    }
+
+    pub fn n1(&self) -> f64 {
+        self.n1
+    }
+
+    pub fn n2(&self) -> f64 {
+        self.n2
+    }
+
+    /// Approximates the Fresnel equations and returns the fraction of light that is reflected.
+    pub fn schlick(&self) -> f64 {
+        // find the cosine of the angle between the eye and normal vectors
+        let mut cos = self.eyev.dot(&self.normalv);
+
+        // total internal reflection can only occur if n1 > n2
+        if self.n1 > self.n2 {
+            let n = self.n1 / self.n2;
+            let sin2_t = n.powi(2) * (1.0 - cos.powi(2));
+            if sin2_t > 1.0 {
+                return 1.0;
+            }
+
+            // when n1 > n2, use cos(theta_t) instead
+            cos = (1.0 - sin2_t).sqrt();
+        }
+
+        let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+    }
+}
+
+pub fn prepare_computations<'a>(hit: &Intersection<'a>, ray: &Ray, xs: &[Intersection<'a>]) -> Computations<'a> {
+    let object = hit.object();
+    let point = ray.position(hit.t());
+    let eyev = -ray.direction();
+    let mut normalv = object.normal_at_hit(&point, hit);
+
+    // if the normal points away from the eye, the hit occurred inside the object
+    let inside = normalv.dot(&eyev) < 0.0;
+    if inside {
+        normalv = -normalv;
+    }
+
+    let over_point = &point + &normalv * EPSILON;
+    let under_point = &point - &(&normalv * EPSILON);
+    let reflectv = ray.direction().reflect(&normalv);
+    let (n1, n2) = refractive_indices(hit, xs);
+
+    Computations { t: hit.t(), object, point, eyev, normalv, inside, over_point, under_point, reflectv, n1, n2 }
+}
+
+// Walks the sorted intersections and keeps track of the objects the ray is currently inside of.
+// n1 belongs to the object the ray leaves at the hit, n2 to the object it enters.
+fn refractive_indices(hit: &Intersection, xs: &[Intersection]) -> (f64, f64) {
+    let mut containers: Vec<&dyn Object> = vec![];
+    let mut n1 = 1.0;
+    let mut n2 = 1.0;
+
+    for i in xs.iter() {
+        if i == hit {
+            n1 = containers.last().map_or(1.0, |o| o.material().refractive_index());
+        }
+
+        match containers.iter().position(|o| std::ptr::addr_eq(*o, i.object())) {
+            Some(index) => { containers.remove(index); },
+            None => containers.push(i.object()),
+        }
+
+        if i == hit {
+            n2 = containers.last().map_or(1.0, |o| o.material().refractive_index());
+            break;
+        }
+    }
+    (n1, n2)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    use float_cmp::approx_eq;
+
+    use crate::raytracer::{objects::{materials::Material, sphere::Sphere}, transformation};
+
+    use super::*;
+
+    pub(crate) fn glass_sphere() -> Sphere {
+        let mut s = Sphere::default();
+        let mut m = Material::default();
+        m.set_transparency(1.0);
+        m.set_refractive_index(1.5);
+        s.set_material(m);
+        s
+    }
+
+    #[test]
+    fn prepare_computations_sets_members() {
+        // Given
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let shape = Sphere::default();
+        let i = Intersection::new(4.0, &shape);
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+
+        // Then
+        assert_eq!(comps.t(), i.t());
+        assert!(std::ptr::addr_eq(comps.object(), &shape));
+        assert_eq!(comps.point(), &Tuple::point(0.0, 0.0, -1.0));
+        assert_eq!(comps.eyev(), &Tuple::vector(0.0, 0.0, -1.0));
+        assert_eq!(comps.normalv(), &Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn prepare_computations_hit_outside() {
+        // Given
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let shape = Sphere::default();
+        let i = Intersection::new(4.0, &shape);
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+
+        // Then
+        assert!(!comps.inside());
+    }
+
+    #[test]
+    fn prepare_computations_hit_inside() {
+        // Given
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        let shape = Sphere::default();
+        let i = Intersection::new(1.0, &shape);
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+
+        // Then
+        assert_eq!(comps.point(), &Tuple::point(0.0, 0.0, 1.0));
+        assert_eq!(comps.eyev(), &Tuple::vector(0.0, 0.0, -1.0));
+        assert!(comps.inside());
+        // normal would have been (0, 0, 1), but is inverted
+        assert_eq!(comps.normalv(), &Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn prepare_computations_offsets_over_point() {
+        // Given
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let mut shape = Sphere::default();
+        shape.set_transform(transformation::translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+
+        // Then
+        assert!(comps.over_point().z() < -EPSILON / 2.0);
+        assert!(comps.point().z() > comps.over_point().z());
+    }
+
+    #[test]
+    fn prepare_computations_offsets_under_point() {
+        // Given
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let mut shape = glass_sphere();
+        shape.set_transform(transformation::translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+        let xs = vec![Intersection::new(5.0, &shape)];
+
+        // When
+        let comps = prepare_computations(&i, &r, &xs);
+
+        // Then
+        assert!(comps.under_point().z() > EPSILON / 2.0);
+        assert!(comps.point().z() < comps.under_point().z());
+    }
+
+    #[test]
+    fn prepare_computations_reflection_vector() {
+        // Given
+        let shape = Sphere::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -2.0), Tuple::vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let i = Intersection::new(1.0, &shape);
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+
+        // Then
+        let expected = r.direction().reflect(comps.normalv());
+        assert_eq!(comps.reflectv(), &expected);
+    }
+
+    #[test]
+    fn prepare_computations_n1_n2_at_various_intersections() {
+        // Given
+        let mut a = glass_sphere();
+        a.set_transform(transformation::scaling(2.0, 2.0, 2.0));
+        let mut b = glass_sphere();
+        b.set_transform(transformation::translation(0.0, 0.0, -0.25));
+        let mut m = b.material().clone();
+        m.set_refractive_index(2.0);
+        b.set_material(m);
+        let mut c = glass_sphere();
+        c.set_transform(transformation::translation(0.0, 0.0, 0.25));
+        let mut m = c.material().clone();
+        m.set_refractive_index(2.5);
+        c.set_material(m);
+
+        let r = Ray::new(Tuple::point(0.0, 0.0, -4.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = vec![
+            Intersection::new(2.0, &a),
+            Intersection::new(2.75, &b),
+            Intersection::new(3.25, &c),
+            Intersection::new(4.75, &b),
+            Intersection::new(5.25, &c),
+            Intersection::new(6.0, &a),
+        ];
+        let expected = [(1.0, 1.5), (1.5, 2.0), (2.0, 2.5), (2.5, 2.5), (2.5, 1.5), (1.5, 1.0)];
+
+        for (i, (n1, n2)) in expected.iter().enumerate() {
+            // When
+            let comps = prepare_computations(&xs[i], &r, &xs);
+
+            // Then
+            assert_eq!(comps.n1(), *n1);
+            assert_eq!(comps.n2(), *n2);
+        }
+    }
+
+    #[test]
+    fn schlick_under_total_internal_reflection() {
+        // Given
+        let shape = glass_sphere();
+        let r = Ray::new(Tuple::point(0.0, 0.0, FRAC_1_SQRT_2), Tuple::vector(0.0, 1.0, 0.0));
+        let xs = vec![Intersection::new(-FRAC_1_SQRT_2, &shape), Intersection::new(FRAC_1_SQRT_2, &shape)];
+
+        // When
+        let comps = prepare_computations(&xs[1], &r, &xs);
+
+        // Then
+        assert_eq!(comps.schlick(), 1.0);
+    }
+
+    #[test]
+    fn schlick_with_perpendicular_viewing_angle() {
+        // Given
+        let shape = glass_sphere();
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+        let xs = vec![Intersection::new(-1.0, &shape), Intersection::new(1.0, &shape)];
+
+        // When
+        let comps = prepare_computations(&xs[1], &r, &xs);
+
+        // Then
+        assert!(approx_eq!(f64, comps.schlick(), 0.04, epsilon = 0.00001));
+    }
+
+    #[test]
+    fn schlick_with_small_angle_and_n2_greater_than_n1() {
+        // Given
+        let shape = glass_sphere();
+        let r = Ray::new(Tuple::point(0.0, 0.99, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = vec![Intersection::new(1.8589, &shape)];
+
+        // When
+        let comps = prepare_computations(&xs[0], &r, &xs);
+
+        // Then
+        assert!(approx_eq!(f64, comps.schlick(), 0.48873, epsilon = 0.00001));
+    }
+}
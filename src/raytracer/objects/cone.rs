@@ -0,0 +1,242 @@
+use crate::raytracer::{ray::Ray, tuple::Tuple, EPSILON};
+
+use super::{bounds::BoundingBox, cylinder::check_cap, intersection::Intersection, materials::Material, object::Object, transform::Transforms};
+
+/// A double-napped cone around the y axis whose radius equals |y|, truncated at `minimum` and `maximum`.
+pub struct Cone {
+    transforms: Transforms,
+    material: Material,
+    minimum: f64,
+    maximum: f64,
+    closed: bool,
+}
+
+impl Cone {
+    pub fn new(minimum: f64, maximum: f64, closed: bool) -> Cone {
+        Self { transforms: Transforms::default(), material: Material::default(), minimum, maximum, closed }
+    }
+
+    pub fn minimum(&self) -> f64 {
+        self.minimum
+    }
+
+    pub fn set_minimum(&mut self, minimum: f64) {
+        self.minimum = minimum;
+    }
+
+    pub fn maximum(&self) -> f64 {
+        self.maximum
+    }
+
+    pub fn set_maximum(&mut self, maximum: f64) {
+        self.maximum = maximum;
+    }
+
+    pub fn closed(&self) -> bool {
+        self.closed
+    }
+
+    pub fn set_closed(&mut self, closed: bool) {
+        self.closed = closed;
+    }
+
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        if !self.closed || ray.direction().y().abs() < EPSILON {
+            return;
+        }
+
+        // unlike the cylinder, the radius of each cap is the y value it sits at
+        for y in [self.minimum, self.maximum] {
+            let t = (y - ray.origin().y()) / ray.direction().y();
+            if check_cap(ray, t, y.abs()) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+
+    fn push_if_in_bounds<'a>(&'a self, ray: &Ray, t: f64, xs: &mut Vec<Intersection<'a>>) {
+        let y = ray.origin().y() + t * ray.direction().y();
+        if self.minimum < y && y < self.maximum {
+            xs.push(Intersection::new(t, self));
+        }
+    }
+}
+
+impl Object for Cone {
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let mut xs = vec![];
+
+        let (o, d) = (ray.origin(), ray.direction());
+        let a = d.x().powi(2) - d.y().powi(2) + d.z().powi(2);
+        let b = 2.0 * o.x() * d.x() - 2.0 * o.y() * d.y() + 2.0 * o.z() * d.z();
+        let c = o.x().powi(2) - o.y().powi(2) + o.z().powi(2);
+
+        if a.abs() < EPSILON {
+            // the ray is parallel to one of the cone's halves and hits the other one at most once
+            if b.abs() >= EPSILON {
+                self.push_if_in_bounds(ray, -c / (2.0 * b), &mut xs);
+            }
+        } else {
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return vec![];
+            }
+
+            let mut t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+            let mut t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            self.push_if_in_bounds(ray, t0, &mut xs);
+            self.push_if_in_bounds(ray, t1, &mut xs);
+        }
+
+        self.intersect_caps(ray, &mut xs);
+        xs
+    }
+
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        let dist = point.x().powi(2) + point.z().powi(2);
+
+        if dist < point.y().powi(2) && point.y() >= self.maximum - EPSILON {
+            Tuple::vector(0.0, 1.0, 0.0)
+        } else if dist < point.y().powi(2) && point.y() <= self.minimum + EPSILON {
+            Tuple::vector(0.0, -1.0, 0.0)
+        } else {
+            let mut y = dist.sqrt();
+            if point.y() > 0.0 {
+                y = -y;
+            }
+            Tuple::vector(point.x(), y, point.z())
+        }
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        // the radius of a cone at any y is |y|, so the widest point is at the end furthest from the origin
+        let limit = self.minimum.abs().max(self.maximum.abs());
+        BoundingBox::new(Tuple::point(-limit, self.minimum, -limit), Tuple::point(limit, self.maximum, limit))
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut Transforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+}
+
+impl Default for Cone {
+    fn default() -> Cone {
+        Self::new(f64::NEG_INFINITY, f64::INFINITY, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn intersects_ray_strikes() {
+        // Given
+        let shape = Cone::default();
+        let cases = [
+            (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 5.0, 5.0),
+            (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(1.0, 1.0, 1.0), 8.66025, 8.66025),
+            (Tuple::point(1.0, 1.0, -5.0), Tuple::vector(-0.5, -1.0, 1.0), 4.55006, 49.44994),
+        ];
+
+        for (origin, direction, t0, t1) in cases {
+            // When
+            let xs = shape.intersects(&Ray::new(origin, direction.normalize()));
+
+            // Then
+            assert_eq!(xs.len(), 2);
+            assert!(approx_eq!(f64, xs[0].t(), t0, epsilon = 0.0001));
+            assert!(approx_eq!(f64, xs[1].t(), t1, epsilon = 0.0001));
+        }
+    }
+
+    #[test]
+    fn intersects_ray_parallel_to_one_half() {
+        // Given
+        let shape = Cone::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -1.0), Tuple::vector(0.0, 1.0, 1.0).normalize());
+
+        // When
+        let xs = shape.intersects(&r);
+
+        // Then
+        assert_eq!(xs.len(), 1);
+        assert!(approx_eq!(f64, xs[0].t(), 0.35355, epsilon = 0.00001));
+    }
+
+    #[test]
+    fn intersects_caps_of_closed_cone() {
+        // Given
+        let shape = Cone::new(-0.5, 0.5, true);
+        let cases = [
+            (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0), 0),
+            (Tuple::point(0.0, 0.0, -0.25), Tuple::vector(0.0, 1.0, 1.0), 2),
+            (Tuple::point(0.0, 0.0, -0.25), Tuple::vector(0.0, 1.0, 0.0), 4),
+        ];
+
+        for (origin, direction, count) in cases {
+            // When
+            let xs = shape.intersects(&Ray::new(origin, direction.normalize()));
+
+            // Then
+            assert_eq!(xs.len(), count);
+        }
+    }
+
+    #[test]
+    fn normal_on_surface() {
+        // Given
+        let shape = Cone::default();
+        let cases = [
+            (Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 0.0)),
+            (Tuple::point(1.0, 1.0, 1.0), Tuple::vector(1.0, -2_f64.sqrt(), 1.0)),
+            (Tuple::point(-1.0, -1.0, 0.0), Tuple::vector(-1.0, 1.0, 0.0)),
+        ];
+
+        for (point, normal) in cases {
+            // When & Then
+            assert_eq!(shape.local_normal_at(&point), normal);
+        }
+    }
+
+    #[test]
+    fn normal_on_caps() {
+        // Given
+        let shape = Cone::new(-1.0, 1.0, true);
+
+        // When & Then
+        assert_eq!(shape.local_normal_at(&Tuple::point(0.0, 1.0, 0.5)), Tuple::vector(0.0, 1.0, 0.0));
+        assert_eq!(shape.local_normal_at(&Tuple::point(0.5, -1.0, 0.0)), Tuple::vector(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn local_bounds_of_truncated_cone() {
+        // Given
+        let shape = Cone::new(-5.0, 3.0, false);
+
+        // When
+        let b = shape.local_bounds();
+
+        // Then
+        assert_eq!(b.min(), &Tuple::point(-5.0, -5.0, -5.0));
+        assert_eq!(b.max(), &Tuple::point(5.0, 3.0, 5.0));
+    }
+}
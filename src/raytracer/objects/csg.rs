@@ -0,0 +1,326 @@
+use crate::raytracer::{matrix::Matrix, ray::Ray, tuple::Tuple};
+
+use super::{bounds::BoundingBox, intersection::Intersection, materials::Material, object::Object, transform::{Transform, Transforms}};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsgOperation {
+    /// Keeps the surfaces of both objects that are not inside the other one.
+    Union,
+    /// Keeps only the volume both objects share.
+    Intersection,
+    /// Cuts the right object out of the left one.
+    Difference,
+}
+
+impl CsgOperation {
+    /// Decides whether a hit on the left (`lhit`) or right object is part of the combined surface,
+    /// given whether the ray is currently inside the left (`inl`) and right (`inr`) object.
+    pub fn intersection_allowed(&self, lhit: bool, inl: bool, inr: bool) -> bool {
+        match self {
+            CsgOperation::Union => (lhit && !inr) || (!lhit && !inl),
+            CsgOperation::Intersection => (lhit && inr) || (!lhit && inl),
+            CsgOperation::Difference => (lhit && !inr) || (!lhit && inl),
+        }
+    }
+}
+
+/// Constructive solid geometry, combining two objects into a single shape.
+pub struct Csg {
+    transforms: Transforms,
+    material: Material,
+    operation: CsgOperation,
+    left: Box<dyn Object>,
+    right: Box<dyn Object>,
+    bounds: BoundingBox,
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: Box<dyn Object>, right: Box<dyn Object>) -> Csg {
+        let mut bounds = left.bounds();
+        bounds.merge(&right.bounds());
+        let mut csg = Self {
+            transforms: Transforms::default(),
+            material: Material::default(),
+            operation,
+            left,
+            right,
+            bounds,
+        };
+        csg.update_children();
+        csg
+    }
+
+    pub fn operation(&self) -> CsgOperation {
+        self.operation
+    }
+
+    pub fn left(&self) -> &dyn Object {
+        self.left.as_ref()
+    }
+
+    pub fn right(&self) -> &dyn Object {
+        self.right.as_ref()
+    }
+
+    /// Keeps the intersections (sorted by `t`) that lie on the surface of the combined shape.
+    pub fn filter_intersections<'a>(&self, xs: Vec<Intersection<'a>>) -> Vec<Intersection<'a>> {
+        let mut inl = false;
+        let mut inr = false;
+        let mut result = vec![];
+
+        for i in xs {
+            let lhit = self.left.includes(i.object());
+            let allowed = self.operation.intersection_allowed(lhit, inl, inr);
+
+            // every hit on a child means the ray either enters or leaves it
+            if lhit {
+                inl = !inl;
+            } else {
+                inr = !inr;
+            }
+
+            if allowed {
+                result.push(i);
+            }
+        }
+
+        result
+    }
+
+    // Like groups, the children are transformed by the transforms of the shape and its parents.
+    fn update_children(&mut self) {
+        let world_transform = self.transforms.world();
+        self.left.set_parent_transform(world_transform.clone());
+        self.right.set_parent_transform(world_transform);
+    }
+}
+
+impl Object for Csg {
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        if !self.bounds.intersects(ray) {
+            return vec![];
+        }
+
+        let mut xs = self.left.intersects(ray);
+        xs.extend(self.right.intersects(ray));
+        xs.sort_by(|a, b| a.t().total_cmp(&b.t()));
+        self.filter_intersections(xs)
+    }
+
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        panic!("CSG shapes have no normals, the normal has to be computed on the child that was hit");
+    }
+
+    fn includes(&self, other: &dyn Object) -> bool {
+        self.left.includes(other) || self.right.includes(other)
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        self.left.divide(threshold);
+        self.right.divide(threshold);
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        self.bounds.clone()
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut Transforms {
+        &mut self.transforms
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transforms.set_own(transform);
+        self.update_children();
+    }
+
+    fn set_parent_transform(&mut self, parent_transform: Transform) {
+        self.transforms.set_parent(parent_transform);
+        self.update_children();
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    /// Sets the material on the shape and both of its children.
+    fn set_material(&mut self, material: Material) {
+        self.left.set_material(material.clone());
+        self.right.set_material(material.clone());
+        self.material = material;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::raytracer::{objects::{cube::Cube, group::Group, sphere::Sphere}, transformation};
+
+    use super::*;
+
+    #[test]
+    fn new_sets_members() {
+        // When
+        let c = Csg::new(CsgOperation::Union, Box::new(Sphere::default()), Box::new(Cube::default()));
+
+        // Then
+        assert_eq!(c.operation(), CsgOperation::Union);
+        assert_eq!(c.transform(), &Matrix::identity(4, 4));
+        assert_eq!(c.left().parent_transform(), &Matrix::identity(4, 4));
+        assert_eq!(c.right().parent_transform(), &Matrix::identity(4, 4));
+    }
+
+    #[test]
+    fn intersection_allowed_follows_operation_rules() {
+        // Given
+        let cases = [
+            (CsgOperation::Union, true, true, true, false),
+            (CsgOperation::Union, true, true, false, true),
+            (CsgOperation::Union, true, false, true, false),
+            (CsgOperation::Union, true, false, false, true),
+            (CsgOperation::Union, false, true, true, false),
+            (CsgOperation::Union, false, true, false, false),
+            (CsgOperation::Union, false, false, true, true),
+            (CsgOperation::Union, false, false, false, true),
+            (CsgOperation::Intersection, true, true, true, true),
+            (CsgOperation::Intersection, true, true, false, false),
+            (CsgOperation::Intersection, true, false, true, true),
+            (CsgOperation::Intersection, true, false, false, false),
+            (CsgOperation::Intersection, false, true, true, true),
+            (CsgOperation::Intersection, false, true, false, true),
+            (CsgOperation::Intersection, false, false, true, false),
+            (CsgOperation::Intersection, false, false, false, false),
+            (CsgOperation::Difference, true, true, true, false),
+            (CsgOperation::Difference, true, true, false, true),
+            (CsgOperation::Difference, true, false, true, false),
+            (CsgOperation::Difference, true, false, false, true),
+            (CsgOperation::Difference, false, true, true, true),
+            (CsgOperation::Difference, false, true, false, true),
+            (CsgOperation::Difference, false, false, true, false),
+            (CsgOperation::Difference, false, false, false, false),
+        ];
+
+        for (operation, lhit, inl, inr, expected) in cases {
+            // When & Then
+            assert_eq!(operation.intersection_allowed(lhit, inl, inr), expected);
+        }
+    }
+
+    #[test]
+    fn filter_intersections_keeps_hits_of_operation() {
+        // Given
+        let cases = [
+            (CsgOperation::Union, 0, 3),
+            (CsgOperation::Intersection, 1, 2),
+            (CsgOperation::Difference, 0, 1),
+        ];
+
+        for (operation, x0, x1) in cases {
+            let c = Csg::new(operation, Box::new(Sphere::default()), Box::new(Cube::default()));
+            let xs = vec![
+                Intersection::new(1.0, c.left()),
+                Intersection::new(2.0, c.right()),
+                Intersection::new(3.0, c.left()),
+                Intersection::new(4.0, c.right()),
+            ];
+            let expected = [xs[x0].t(), xs[x1].t()];
+
+            // When
+            let result = c.filter_intersections(xs);
+
+            // Then
+            assert_eq!(result.len(), 2);
+            assert_eq!([result[0].t(), result[1].t()], expected);
+        }
+    }
+
+    #[test]
+    fn filter_intersections_looks_into_nested_groups() {
+        // Given
+        let left = Group::new(vec![Box::new(Sphere::default())]);
+        let mut right = Cube::default();
+        right.set_transform(transformation::scaling(2.0, 2.0, 2.0));
+        let c = Csg::new(CsgOperation::Union, Box::new(left), Box::new(right));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let mut xs = c.left().intersects(&r);
+        xs.extend(c.right().intersects(&r));
+        xs.sort_by(|a, b| a.t().total_cmp(&b.t()));
+
+        // When
+        let result = c.filter_intersections(xs);
+
+        // Then
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].t(), 3.0);
+        assert_eq!(result[1].t(), 7.0);
+    }
+
+    #[test]
+    fn intersects_ray_misses() {
+        // Given
+        let c = Csg::new(CsgOperation::Union, Box::new(Sphere::default()), Box::new(Cube::default()));
+        let r = Ray::new(Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = c.local_intersect(&r);
+
+        // Then
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn intersects_ray_hits() {
+        // Given
+        let mut s2 = Sphere::default();
+        s2.set_transform(transformation::translation(0.0, 0.0, 0.5));
+        let c = Csg::new(CsgOperation::Union, Box::new(Sphere::default()), Box::new(s2));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = c.local_intersect(&r);
+
+        // Then
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t(), 4.0);
+        assert!(std::ptr::addr_eq(xs[0].object(), c.left()));
+        assert_eq!(xs[1].t(), 6.5);
+        assert!(std::ptr::addr_eq(xs[1].object(), c.right()));
+    }
+
+    #[test]
+    fn intersects_lens_from_two_spheres() {
+        // Given
+        let mut s1 = Sphere::default();
+        s1.set_transform(transformation::translation(0.0, 0.0, -0.5));
+        let mut s2 = Sphere::default();
+        s2.set_transform(transformation::translation(0.0, 0.0, 0.5));
+        let mut c = Csg::new(CsgOperation::Intersection, Box::new(s1), Box::new(s2));
+        c.set_transform(transformation::scaling(2.0, 2.0, 2.0));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = c.intersects(&r);
+
+        // Then
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t(), 4.0);
+        assert_eq!(xs[1].t(), 6.0);
+        assert_eq!(xs[0].object().normal_at(&Tuple::point(0.0, 0.0, -1.0)), Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn local_bounds_contains_children() {
+        // Given
+        let mut right = Sphere::default();
+        right.set_transform(transformation::translation(2.0, 3.0, 4.0));
+
+        // When
+        let c = Csg::new(CsgOperation::Difference, Box::new(Sphere::default()), Box::new(right));
+
+        // Then
+        assert_eq!(c.local_bounds().min(), &Tuple::point(-1.0, -1.0, -1.0));
+        assert_eq!(c.local_bounds().max(), &Tuple::point(3.0, 4.0, 5.0));
+    }
+}
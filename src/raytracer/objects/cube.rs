@@ -0,0 +1,165 @@
+use crate::raytracer::{ray::Ray, tuple::Tuple};
+
+use super::{bounds::{check_axis, BoundingBox}, intersection::Intersection, materials::Material, object::Object, transform::Transforms};
+
+/// An axis-aligned cube spanning -1 to 1 on every axis in object space.
+#[derive(Default)]
+pub struct Cube {
+    transforms: Transforms,
+    material: Material
+}
+
+impl Object for Cube {
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let (xtmin, xtmax) = check_axis(ray.origin().x(), ray.direction().x(), -1.0, 1.0);
+        let (ytmin, ytmax) = check_axis(ray.origin().y(), ray.direction().y(), -1.0, 1.0);
+        let (ztmin, ztmax) = check_axis(ray.origin().z(), ray.direction().z(), -1.0, 1.0);
+
+        // the ray enters the cube at the latest entry and leaves it at the earliest exit
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+
+        if tmin > tmax {
+            return vec![];
+        }
+
+        vec![Intersection::new(tmin, self), Intersection::new(tmax, self)]
+    }
+
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        // the face the point lies on is the one of the component with the largest magnitude
+        let maxc = point.x().abs().max(point.y().abs()).max(point.z().abs());
+
+        if maxc == point.x().abs() {
+            Tuple::vector(point.x(), 0.0, 0.0)
+        } else if maxc == point.y().abs() {
+            Tuple::vector(0.0, point.y(), 0.0)
+        } else {
+            Tuple::vector(0.0, 0.0, point.z())
+        }
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut Transforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::raytracer::transformation;
+
+    use super::*;
+
+    #[test]
+    fn intersects_ray_from_each_face() {
+        // Given
+        let c = Cube::default();
+        let cases = [
+            (Tuple::point(5.0, 0.5, 0.0), Tuple::vector(-1.0, 0.0, 0.0), 4.0, 6.0),
+            (Tuple::point(-5.0, 0.5, 0.0), Tuple::vector(1.0, 0.0, 0.0), 4.0, 6.0),
+            (Tuple::point(0.5, 5.0, 0.0), Tuple::vector(0.0, -1.0, 0.0), 4.0, 6.0),
+            (Tuple::point(0.5, -5.0, 0.0), Tuple::vector(0.0, 1.0, 0.0), 4.0, 6.0),
+            (Tuple::point(0.5, 0.0, 5.0), Tuple::vector(0.0, 0.0, -1.0), 4.0, 6.0),
+            (Tuple::point(0.5, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 4.0, 6.0),
+            (Tuple::point(0.0, 0.5, 0.0), Tuple::vector(0.0, 0.0, 1.0), -1.0, 1.0),
+        ];
+
+        for (origin, direction, t1, t2) in cases {
+            // When
+            let xs = c.intersects(&Ray::new(origin, direction));
+
+            // Then
+            assert_eq!(xs.len(), 2);
+            assert_eq!(xs[0].t(), t1);
+            assert_eq!(xs[1].t(), t2);
+        }
+    }
+
+    #[test]
+    fn intersects_ray_misses() {
+        // Given
+        let c = Cube::default();
+        let cases = [
+            (Tuple::point(-2.0, 0.0, 0.0), Tuple::vector(0.2673, 0.5345, 0.8018)),
+            (Tuple::point(0.0, -2.0, 0.0), Tuple::vector(0.8018, 0.2673, 0.5345)),
+            (Tuple::point(0.0, 0.0, -2.0), Tuple::vector(0.5345, 0.8018, 0.2673)),
+            (Tuple::point(2.0, 0.0, 2.0), Tuple::vector(0.0, 0.0, -1.0)),
+            (Tuple::point(0.0, 2.0, 2.0), Tuple::vector(0.0, -1.0, 0.0)),
+            (Tuple::point(2.0, 2.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0)),
+        ];
+
+        for (origin, direction) in cases {
+            // When
+            let xs = c.intersects(&Ray::new(origin, direction));
+
+            // Then
+            assert_eq!(xs.len(), 0);
+        }
+    }
+
+    #[test]
+    fn normal_on_surface() {
+        // Given
+        let c = Cube::default();
+        let cases = [
+            (Tuple::point(1.0, 0.5, -0.8), Tuple::vector(1.0, 0.0, 0.0)),
+            (Tuple::point(-1.0, -0.2, 0.9), Tuple::vector(-1.0, 0.0, 0.0)),
+            (Tuple::point(-0.4, 1.0, -0.1), Tuple::vector(0.0, 1.0, 0.0)),
+            (Tuple::point(0.3, -1.0, -0.7), Tuple::vector(0.0, -1.0, 0.0)),
+            (Tuple::point(-0.6, 0.3, 1.0), Tuple::vector(0.0, 0.0, 1.0)),
+            (Tuple::point(0.4, 0.4, -1.0), Tuple::vector(0.0, 0.0, -1.0)),
+            (Tuple::point(1.0, 1.0, 1.0), Tuple::vector(1.0, 0.0, 0.0)),
+            (Tuple::point(-1.0, -1.0, -1.0), Tuple::vector(-1.0, 0.0, 0.0)),
+        ];
+
+        for (point, normal) in cases {
+            // When & Then
+            assert_eq!(c.local_normal_at(&point), normal);
+        }
+    }
+
+    #[test]
+    fn intersects_applies_transform() {
+        // Given
+        let mut c = Cube::default();
+        c.set_transform(transformation::scaling(2.0, 2.0, 2.0));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = c.intersects(&r);
+
+        // Then
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t(), 3.0);
+        assert_eq!(xs[1].t(), 7.0);
+    }
+
+    #[test]
+    fn normal_on_translated_cube() {
+        // Given
+        let mut c = Cube::default();
+        c.set_transform(transformation::translation(0.0, 1.0, 0.0));
+
+        // When
+        let n = c.normal_at(&Tuple::point(0.5, 2.0, 0.0));
+
+        // Then
+        assert_eq!(n, Tuple::vector(0.0, 1.0, 0.0));
+    }
+}
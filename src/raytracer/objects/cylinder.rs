@@ -0,0 +1,289 @@
+use crate::raytracer::{ray::Ray, tuple::Tuple, EPSILON};
+
+use super::{bounds::BoundingBox, intersection::Intersection, materials::Material, object::Object, transform::Transforms};
+
+/// A cylinder of radius 1 around the y axis, truncated at `minimum` and `maximum` (both exclusive).
+pub struct Cylinder {
+    transforms: Transforms,
+    material: Material,
+    minimum: f64,
+    maximum: f64,
+    closed: bool,
+}
+
+impl Cylinder {
+    pub fn new(minimum: f64, maximum: f64, closed: bool) -> Cylinder {
+        Self { transforms: Transforms::default(), material: Material::default(), minimum, maximum, closed }
+    }
+
+    pub fn minimum(&self) -> f64 {
+        self.minimum
+    }
+
+    pub fn set_minimum(&mut self, minimum: f64) {
+        self.minimum = minimum;
+    }
+
+    pub fn maximum(&self) -> f64 {
+        self.maximum
+    }
+
+    pub fn set_maximum(&mut self, maximum: f64) {
+        self.maximum = maximum;
+    }
+
+    pub fn closed(&self) -> bool {
+        self.closed
+    }
+
+    pub fn set_closed(&mut self, closed: bool) {
+        self.closed = closed;
+    }
+
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        // caps only matter if the cylinder is closed and might possibly be intersected by the ray
+        if !self.closed || ray.direction().y().abs() < EPSILON {
+            return;
+        }
+
+        for y in [self.minimum, self.maximum] {
+            let t = (y - ray.origin().y()) / ray.direction().y();
+            if check_cap(ray, t, 1.0) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+impl Object for Cylinder {
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let mut xs = vec![];
+
+        let a = ray.direction().x().powi(2) + ray.direction().z().powi(2);
+
+        // a ray parallel to the y axis can only hit the caps
+        if a.abs() >= EPSILON {
+            let b = 2.0 * ray.origin().x() * ray.direction().x() + 2.0 * ray.origin().z() * ray.direction().z();
+            let c = ray.origin().x().powi(2) + ray.origin().z().powi(2) - 1.0;
+
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return vec![];
+            }
+
+            let mut t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+            let mut t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            for t in [t0, t1] {
+                let y = ray.origin().y() + t * ray.direction().y();
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(t, self));
+                }
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+        xs
+    }
+
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        // the square of the distance from the y axis
+        let dist = point.x().powi(2) + point.z().powi(2);
+
+        if dist < 1.0 && point.y() >= self.maximum - EPSILON {
+            Tuple::vector(0.0, 1.0, 0.0)
+        } else if dist < 1.0 && point.y() <= self.minimum + EPSILON {
+            Tuple::vector(0.0, -1.0, 0.0)
+        } else {
+            Tuple::vector(point.x(), 0.0, point.z())
+        }
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        BoundingBox::new(Tuple::point(-1.0, self.minimum, -1.0), Tuple::point(1.0, self.maximum, 1.0))
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut Transforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+}
+
+impl Default for Cylinder {
+    fn default() -> Cylinder {
+        Self::new(f64::NEG_INFINITY, f64::INFINITY, false)
+    }
+}
+
+/// Checks whether the intersection at `t` is within `radius` of the y axis.
+pub(crate) fn check_cap(ray: &Ray, t: f64, radius: f64) -> bool {
+    let x = ray.origin().x() + t * ray.direction().x();
+    let z = ray.origin().z() + t * ray.direction().z();
+    x.powi(2) + z.powi(2) <= radius.powi(2) + EPSILON
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn intersects_ray_misses() {
+        // Given
+        let cyl = Cylinder::default();
+        let cases = [
+            (Tuple::point(1.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+            (Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+            (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(1.0, 1.0, 1.0)),
+        ];
+
+        for (origin, direction) in cases {
+            // When
+            let xs = cyl.intersects(&Ray::new(origin, direction.normalize()));
+
+            // Then
+            assert_eq!(xs.len(), 0);
+        }
+    }
+
+    #[test]
+    fn intersects_ray_strikes() {
+        // Given
+        let cyl = Cylinder::default();
+        let cases = [
+            (Tuple::point(1.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 5.0, 5.0),
+            (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 4.0, 6.0),
+            (Tuple::point(0.5, 0.0, -5.0), Tuple::vector(0.1, 1.0, 1.0), 6.80798, 7.08872),
+        ];
+
+        for (origin, direction, t0, t1) in cases {
+            // When
+            let xs = cyl.intersects(&Ray::new(origin, direction.normalize()));
+
+            // Then
+            assert_eq!(xs.len(), 2);
+            assert!(approx_eq!(f64, xs[0].t(), t0, epsilon = 0.00001));
+            assert!(approx_eq!(f64, xs[1].t(), t1, epsilon = 0.00001));
+        }
+    }
+
+    #[test]
+    fn normal_on_surface() {
+        // Given
+        let cyl = Cylinder::default();
+        let cases = [
+            (Tuple::point(1.0, 0.0, 0.0), Tuple::vector(1.0, 0.0, 0.0)),
+            (Tuple::point(0.0, 5.0, -1.0), Tuple::vector(0.0, 0.0, -1.0)),
+            (Tuple::point(0.0, -2.0, 1.0), Tuple::vector(0.0, 0.0, 1.0)),
+            (Tuple::point(-1.0, 1.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0)),
+        ];
+
+        for (point, normal) in cases {
+            // When & Then
+            assert_eq!(cyl.local_normal_at(&point), normal);
+        }
+    }
+
+    #[test]
+    fn default_is_infinite_and_open() {
+        // When
+        let cyl = Cylinder::default();
+
+        // Then
+        assert_eq!(cyl.minimum(), f64::NEG_INFINITY);
+        assert_eq!(cyl.maximum(), f64::INFINITY);
+        assert!(!cyl.closed());
+    }
+
+    #[test]
+    fn intersects_constrained_cylinder() {
+        // Given
+        let cyl = Cylinder::new(1.0, 2.0, false);
+        let cases = [
+            (Tuple::point(0.0, 1.5, 0.0), Tuple::vector(0.1, 1.0, 0.0), 0),
+            (Tuple::point(0.0, 3.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 0),
+            (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 0),
+            (Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 0),
+            (Tuple::point(0.0, 1.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 0),
+            (Tuple::point(0.0, 1.5, -2.0), Tuple::vector(0.0, 0.0, 1.0), 2),
+        ];
+
+        for (origin, direction, count) in cases {
+            // When
+            let xs = cyl.intersects(&Ray::new(origin, direction.normalize()));
+
+            // Then
+            assert_eq!(xs.len(), count);
+        }
+    }
+
+    #[test]
+    fn intersects_caps_of_closed_cylinder() {
+        // Given
+        let cyl = Cylinder::new(1.0, 2.0, true);
+        let cases = [
+            (Tuple::point(0.0, 3.0, 0.0), Tuple::vector(0.0, -1.0, 0.0), 2),
+            (Tuple::point(0.0, 3.0, -2.0), Tuple::vector(0.0, -1.0, 2.0), 2),
+            // corner case
+            (Tuple::point(0.0, 4.0, -2.0), Tuple::vector(0.0, -1.0, 1.0), 2),
+            (Tuple::point(0.0, 0.0, -2.0), Tuple::vector(0.0, 1.0, 2.0), 2),
+            // corner case
+            (Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 1.0, 1.0), 2),
+        ];
+
+        for (origin, direction, count) in cases {
+            // When
+            let xs = cyl.intersects(&Ray::new(origin, direction.normalize()));
+
+            // Then
+            assert_eq!(xs.len(), count);
+        }
+    }
+
+    #[test]
+    fn normal_on_caps() {
+        // Given
+        let cyl = Cylinder::new(1.0, 2.0, true);
+        let cases = [
+            (Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0)),
+            (Tuple::point(0.5, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0)),
+            (Tuple::point(0.0, 1.0, 0.5), Tuple::vector(0.0, -1.0, 0.0)),
+            (Tuple::point(0.0, 2.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+            (Tuple::point(0.5, 2.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+            (Tuple::point(0.0, 2.0, 0.5), Tuple::vector(0.0, 1.0, 0.0)),
+        ];
+
+        for (point, normal) in cases {
+            // When & Then
+            assert_eq!(cyl.local_normal_at(&point), normal);
+        }
+    }
+
+    #[test]
+    fn local_bounds_of_truncated_cylinder() {
+        // Given
+        let cyl = Cylinder::new(-5.0, 3.0, false);
+
+        // When
+        let b = cyl.local_bounds();
+
+        // Then
+        assert_eq!(b.min(), &Tuple::point(-1.0, -5.0, -1.0));
+        assert_eq!(b.max(), &Tuple::point(1.0, 3.0, 1.0));
+    }
+}
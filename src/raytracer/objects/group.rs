@@ -0,0 +1,533 @@
+use crate::raytracer::{matrix::Matrix, ray::Ray, tuple::Tuple};
+
+use super::{bounds::BoundingBox, intersection::Intersection, materials::Material, object::Object, transform::{Transform, Transforms}};
+
+type Children = Vec<Box<dyn Object>>;
+
+/// A collection of objects that share the group's transform in addition to their own.
+pub struct Group {
+    transforms: Transforms,
+    material: Material,
+    children: Vec<Box<dyn Object>>,
+    // cached, as the bounds of the children can't change once they are part of the group
+    bounds: BoundingBox,
+}
+
+impl Group {
+    pub fn new(children: Vec<Box<dyn Object>>) -> Group {
+        let mut group = Self::default();
+        for child in children {
+            group.add_child(child);
+        }
+        group
+    }
+
+    pub fn children(&self) -> &Vec<Box<dyn Object>> {
+        &self.children
+    }
+
+    pub fn add_child(&mut self, mut child: Box<dyn Object>) {
+        child.set_parent_transform(self.transforms.world());
+        self.bounds.merge(&child.bounds());
+        self.children.push(child);
+    }
+
+    // Moves the children that fit entirely into either half of the group's bounds out of the group.
+    // Only finite children span the bounds that are split, unbounded ones like planes stay where they are.
+    fn partition_children(&mut self) -> (Children, Children) {
+        let mut finite_bounds = BoundingBox::empty();
+        for child in self.children.iter().map(|c| c.bounds()).filter(|b| b.is_finite()) {
+            finite_bounds.merge(&child);
+        }
+        let (left_bounds, right_bounds) = finite_bounds.split();
+        let mut left = vec![];
+        let mut right = vec![];
+        let mut remaining = vec![];
+
+        for child in std::mem::take(&mut self.children) {
+            let child_bounds = child.bounds();
+            if left_bounds.contains_box(&child_bounds) {
+                left.push(child);
+            } else if right_bounds.contains_box(&child_bounds) {
+                right.push(child);
+            } else {
+                remaining.push(child);
+            }
+        }
+
+        self.children = remaining;
+        (left, right)
+    }
+
+    fn update_children(&mut self) {
+        let world_transform = self.transforms.world();
+        for child in self.children.iter_mut() {
+            child.set_parent_transform(world_transform.clone());
+        }
+    }
+}
+
+impl Object for Group {
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        if !self.bounds.intersects(ray) {
+            return vec![];
+        }
+
+        let mut xs: Vec<Intersection> = self.children.iter()
+            .flat_map(|c| c.intersects(ray))
+            .collect();
+        xs.sort_by(|a, b| a.t().total_cmp(&b.t()));
+        xs
+    }
+
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        panic!("Groups have no normals, the normal has to be computed on the child that was hit");
+    }
+
+    fn includes(&self, other: &dyn Object) -> bool {
+        self.children.iter().any(|c| c.includes(other))
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        let count = self.children.len();
+        if threshold <= count {
+            let (left, right) = self.partition_children();
+            if left.len() == count || right.len() == count {
+                // all children share the same degenerate bounds, splitting again would never end
+                self.children = left.into_iter().chain(right).collect();
+            } else {
+                for half in [left, right] {
+                    if !half.is_empty() {
+                        self.add_child(Box::new(Group::new(half)));
+                    }
+                }
+            }
+        }
+
+        for child in self.children.iter_mut() {
+            child.divide(threshold);
+        }
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        self.bounds.clone()
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut Transforms {
+        &mut self.transforms
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transforms.set_own(transform);
+        self.update_children();
+    }
+
+    fn set_parent_transform(&mut self, parent_transform: Transform) {
+        self.transforms.set_parent(parent_transform);
+        self.update_children();
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    /// Sets the material on the group and all of its children.
+    fn set_material(&mut self, material: Material) {
+        for child in self.children.iter_mut() {
+            child.set_material(material.clone());
+        }
+        self.material = material;
+    }
+}
+
+impl Default for Group {
+    fn default() -> Group {
+        Self { transforms: Transforms::default(), material: Material::default(), children: vec![], bounds: BoundingBox::empty() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{f64::consts::PI, sync::{atomic::{AtomicUsize, Ordering}, Arc}};
+
+    use crate::raytracer::{color::Color, objects::{cylinder::Cylinder, plane::Plane, sphere::Sphere}, transformation};
+
+    use super::*;
+
+    #[test]
+    fn default_is_empty() {
+        // When
+        let g = Group::default();
+
+        // Then
+        assert_eq!(g.transform(), &Matrix::identity(4, 4));
+        assert!(g.children().is_empty());
+    }
+
+    #[test]
+    fn add_child_sets_parent_transform() {
+        // Given
+        let mut g = Group::default();
+        g.set_transform(transformation::translation(1.0, 0.0, 0.0));
+
+        // When
+        g.add_child(Box::new(Sphere::default()));
+
+        // Then
+        assert_eq!(g.children().len(), 1);
+        assert_eq!(g.children()[0].parent_transform(), &transformation::translation(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn intersects_empty_group() {
+        // Given
+        let g = Group::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = g.local_intersect(&r);
+
+        // Then
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn intersects_nonempty_group() {
+        // Given
+        let s1 = Sphere::default();
+        let mut s2 = Sphere::default();
+        s2.set_transform(transformation::translation(0.0, 0.0, -3.0));
+        let mut s3 = Sphere::default();
+        s3.set_transform(transformation::translation(5.0, 0.0, 0.0));
+        let g = Group::new(vec![Box::new(s1), Box::new(s2), Box::new(s3)]);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = g.local_intersect(&r);
+
+        // Then
+        assert_eq!(xs.len(), 4);
+        assert!(std::ptr::addr_eq(xs[0].object(), g.children()[1].as_ref()));
+        assert!(std::ptr::addr_eq(xs[1].object(), g.children()[1].as_ref()));
+        assert!(std::ptr::addr_eq(xs[2].object(), g.children()[0].as_ref()));
+        assert!(std::ptr::addr_eq(xs[3].object(), g.children()[0].as_ref()));
+    }
+
+    #[test]
+    fn intersects_transformed_group() {
+        // Given
+        let mut s = Sphere::default();
+        s.set_transform(transformation::translation(5.0, 0.0, 0.0));
+        let mut g = Group::new(vec![Box::new(s)]);
+        g.set_transform(transformation::scaling(2.0, 2.0, 2.0));
+        let r = Ray::new(Tuple::point(10.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = g.intersects(&r);
+
+        // Then
+        assert_eq!(xs.len(), 2);
+    }
+
+    fn nested_groups(g2_scaling: Matrix) -> Group {
+        let mut s = Sphere::default();
+        s.set_transform(transformation::translation(5.0, 0.0, 0.0));
+        let mut g2 = Group::default();
+        g2.set_transform(g2_scaling);
+        g2.add_child(Box::new(s));
+        let mut g1 = Group::default();
+        g1.set_transform(transformation::rotation_y(PI / 2.0));
+        g1.add_child(Box::new(g2));
+        g1
+    }
+
+    // the nested sphere ends up on the negative z axis in world space, so this ray hits it
+    fn ray_to_nested_sphere() -> Ray {
+        Ray::new(Tuple::point(0.0, 0.0, -20.0), Tuple::vector(0.0, 0.0, 1.0))
+    }
+
+    #[test]
+    fn world_to_object_through_parent_chain() {
+        // Given
+        let g1 = nested_groups(transformation::scaling(2.0, 2.0, 2.0));
+        let xs = g1.intersects(&ray_to_nested_sphere());
+        let s = xs[0].object();
+
+        // When
+        let p = s.world_to_object(&Tuple::point(-2.0, 0.0, -10.0));
+
+        // Then
+        assert_eq!(p, Tuple::point(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn normal_to_world_through_parent_chain() {
+        // Given
+        let g1 = nested_groups(transformation::scaling(1.0, 2.0, 3.0));
+        let xs = g1.intersects(&ray_to_nested_sphere());
+        let s = xs[0].object();
+        let v = 3_f64.sqrt() / 3.0;
+
+        // When
+        let n = s.normal_to_world(&Tuple::vector(v, v, v));
+
+        // Then
+        assert_eq!(n, Tuple::vector(0.28571, 0.42857, -0.85714));
+    }
+
+    #[test]
+    fn normal_at_child_in_nested_groups() {
+        // Given
+        let g1 = nested_groups(transformation::scaling(1.0, 2.0, 3.0));
+        let xs = g1.intersects(&ray_to_nested_sphere());
+        let s = xs[0].object();
+
+        // When
+        let n = s.normal_at(&Tuple::point(1.7321, 1.1547, -5.5774));
+
+        // Then
+        assert_eq!(n, Tuple::vector(0.28570, 0.42854, -0.85716));
+    }
+
+    #[test]
+    fn set_transform_propagates_to_nested_children() {
+        // Given
+        let mut g1 = nested_groups(transformation::scaling(2.0, 2.0, 2.0));
+
+        // When
+        g1.set_transform(Matrix::identity(4, 4));
+
+        // Then
+        let xs = g1.intersects(&Ray::new(Tuple::point(10.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0)));
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].object().parent_transform(), &transformation::scaling(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn nested_children_get_inverse_of_parent_chain() {
+        // Given
+        let g1 = nested_groups(transformation::scaling(1.0, 2.0, 3.0));
+        let xs = g1.intersects(&ray_to_nested_sphere());
+        let s = xs[0].object();
+
+        // When
+        let expected = s.parent_transform().inverse();
+
+        // Then
+        assert_eq!(s.parent_transform(), &(transformation::rotation_y(PI / 2.0) * &transformation::scaling(1.0, 2.0, 3.0)));
+        assert_eq!(s.parent_transform_inverse(), &expected);
+    }
+
+    #[test]
+    fn includes_nested_children() {
+        // Given
+        let g1 = nested_groups(transformation::scaling(2.0, 2.0, 2.0));
+        let other = Sphere::default();
+        let xs = g1.intersects(&ray_to_nested_sphere());
+
+        // When & Then
+        assert!(g1.includes(xs[0].object()));
+        assert!(!g1.includes(&other));
+    }
+
+    #[test]
+    fn set_material_applies_to_children() {
+        // Given
+        let mut g = Group::new(vec![Box::new(Sphere::default())]);
+        let mut m = Material::default();
+        m.set_color(Color::new(1.0, 0.0, 0.0));
+
+        // When
+        g.set_material(m.clone());
+
+        // Then
+        assert_eq!(g.children()[0].material(), &m);
+    }
+
+    #[test]
+    fn bounds_contain_transformed_children() {
+        // Given
+        let mut s = Sphere::default();
+        s.set_transform(&transformation::translation(2.0, 5.0, -3.0) * &transformation::scaling(2.0, 2.0, 2.0));
+        let mut c = Cylinder::new(-2.0, 2.0, false);
+        c.set_transform(&transformation::translation(-4.0, -1.0, 4.0) * &transformation::scaling(0.5, 1.0, 0.5));
+
+        // When
+        let g = Group::new(vec![Box::new(s), Box::new(c)]);
+
+        // Then
+        assert_eq!(g.local_bounds().min(), &Tuple::point(-4.5, -3.0, -5.0));
+        assert_eq!(g.local_bounds().max(), &Tuple::point(4.0, 7.0, 4.5));
+    }
+
+    #[test]
+    fn bounds_ignore_empty_subgroups() {
+        // Given
+        let mut empty = Group::default();
+        empty.set_transform(transformation::scaling(2.0, 2.0, 2.0));
+        let mut s = Sphere::default();
+        s.set_transform(transformation::translation(2.0, 0.0, 0.0));
+
+        // When
+        let g = Group::new(vec![Box::new(empty), Box::new(s)]);
+
+        // Then
+        assert!(g.local_bounds().is_finite());
+        assert_eq!(g.local_bounds().min(), &Tuple::point(1.0, -1.0, -1.0));
+        assert_eq!(g.local_bounds().max(), &Tuple::point(3.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn intersects_skips_children_when_bounds_missed() {
+        // Given
+        let g = Group::new(vec![Box::new(Sphere::default())]);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+
+        // When & Then
+        assert!(!g.local_bounds().intersects(&r));
+        assert!(g.local_intersect(&r).is_empty());
+    }
+
+    // A sphere that counts how often rays are tested against it.
+    struct CountingSphere {
+        sphere: Sphere,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Object for CountingSphere {
+        fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            self.sphere.local_intersect(ray).iter().map(|i| Intersection::new(i.t(), self)).collect()
+        }
+
+        fn local_normal_at(&self, point: &Tuple) -> Tuple {
+            self.sphere.local_normal_at(point)
+        }
+
+        fn local_bounds(&self) -> BoundingBox {
+            self.sphere.local_bounds()
+        }
+
+        fn transforms(&self) -> &Transforms {
+            self.sphere.transforms()
+        }
+
+        fn transforms_mut(&mut self) -> &mut Transforms {
+            self.sphere.transforms_mut()
+        }
+
+        fn material(&self) -> &Material {
+            self.sphere.material()
+        }
+
+        fn set_material(&mut self, material: Material) {
+            self.sphere.set_material(material);
+        }
+    }
+
+    #[test]
+    fn intersects_tests_children_only_when_bounds_hit() {
+        // Given
+        let calls = Arc::new(AtomicUsize::new(0));
+        let g = Group::new(vec![Box::new(CountingSphere { sphere: Sphere::default(), calls: Arc::clone(&calls) })]);
+        let miss = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+        let hit = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let missed = g.intersects(&miss);
+        let calls_after_miss = calls.load(Ordering::Relaxed);
+        let hits = g.intersects(&hit);
+
+        // Then
+        assert!(missed.is_empty());
+        assert_eq!(calls_after_miss, 0);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    fn sphere_at(x: f64, y: f64, z: f64) -> Box<dyn Object> {
+        let mut s = Sphere::default();
+        s.set_transform(transformation::translation(x, y, z));
+        Box::new(s)
+    }
+
+    #[test]
+    fn partition_children_by_bounds() {
+        // Given
+        let mut g = Group::new(vec![sphere_at(-2.0, 0.0, 0.0), sphere_at(2.0, 0.0, 0.0), Box::new(Sphere::default())]);
+
+        // When
+        let (left, right) = g.partition_children();
+
+        // Then
+        assert_eq!(g.children().len(), 1);
+        assert_eq!(left.len(), 1);
+        assert_eq!(right.len(), 1);
+        assert_eq!(left[0].bounds().min(), &Tuple::point(-3.0, -1.0, -1.0));
+        assert_eq!(right[0].bounds().min(), &Tuple::point(1.0, -1.0, -1.0));
+    }
+
+    #[test]
+    fn divide_creates_subgroups() {
+        // Given
+        let mut g = Group::new(vec![sphere_at(-2.0, -2.0, 0.0), sphere_at(-2.0, 2.0, 0.0), {
+            let mut s = Sphere::default();
+            s.set_transform(transformation::scaling(4.0, 4.0, 4.0));
+            Box::new(s)
+        }]);
+        let r = Ray::new(Tuple::point(-2.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let before: Vec<f64> = g.intersects(&r).iter().map(|i| i.t()).collect();
+
+        // When
+        g.divide(1);
+
+        // Then
+        assert_eq!(g.children().len(), 2);
+        assert_eq!(g.children()[0].bounds().min(), &Tuple::point(-4.0, -4.0, -4.0));
+        let after: Vec<f64> = g.intersects(&r).iter().map(|i| i.t()).collect();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn divide_keeps_unbounded_children_at_top_level() {
+        // Given
+        let mut g = Group::new(vec![sphere_at(-2.0, 0.0, 0.0), sphere_at(2.0, 0.0, 0.0), Box::new(Plane::default())]);
+
+        // When
+        g.divide(1);
+
+        // Then
+        assert_eq!(g.children().len(), 3);
+        assert!(!g.children()[0].bounds().is_finite());
+        assert_eq!(g.children()[1].bounds().min(), &Tuple::point(-3.0, -1.0, -1.0));
+        assert_eq!(g.children()[2].bounds().min(), &Tuple::point(1.0, -1.0, -1.0));
+    }
+
+    #[test]
+    fn divide_below_threshold_keeps_children() {
+        // Given
+        let mut g = Group::new(vec![sphere_at(-2.0, 0.0, 0.0), sphere_at(2.0, 0.0, 0.0)]);
+
+        // When
+        g.divide(3);
+
+        // Then
+        assert_eq!(g.children().len(), 2);
+    }
+
+    #[test]
+    fn divide_stops_on_identical_bounds() {
+        // Given
+        let mut g = Group::new(vec![Box::new(Sphere::default()), Box::new(Sphere::default())]);
+
+        // When
+        g.divide(1);
+
+        // Then
+        assert_eq!(g.children().len(), 2);
+    }
+}
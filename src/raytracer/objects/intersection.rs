@@ -3,27 +3,48 @@ use super::object::Object;
 pub struct Intersection<'a> {
     t: f64,
     object: &'a dyn Object,
+    u: Option<f64>,
+    v: Option<f64>,
 }
 
-impl Intersection<'_> {
+impl<'a> Intersection<'a> {
 
-    pub fn new(t: f64, object: &dyn Object) -> Intersection {
-        Intersection { t, object }
+    pub fn new(t: f64, object: &'a dyn Object) -> Intersection<'a> {
+        Intersection { t, object, u: None, v: None }
     }
 
-    pub fn from_hit<'a>(intersections: &'a Vec<Intersection>) -> Option<&'a Intersection<'a>> {
+    /// Creates an intersection that remembers where on a triangle it occurred, as barycentric `u` and `v` coordinates.
+    pub fn with_uv(t: f64, object: &'a dyn Object, u: f64, v: f64) -> Intersection<'a> {
+        Intersection { t, object, u: Some(u), v: Some(v) }
+    }
+
+    pub fn from_hit<'b>(intersections: &'b [Intersection<'a>]) -> Option<&'b Intersection<'a>> {
         intersections.iter()
             .filter(|i| i.t() >= 0.0)
-            .min_by(|a, b| a.t().partial_cmp(&b.t()).unwrap())
+            .min_by(|a, b| a.t().total_cmp(&b.t()))
     }
 
     pub fn t(&self) -> f64 {
         self.t
     }
 
-    pub fn object(&self) -> &dyn Object {
+    pub fn object(&self) -> &'a dyn Object {
         self.object
     }
+
+    pub fn u(&self) -> Option<f64> {
+        self.u
+    }
+
+    pub fn v(&self) -> Option<f64> {
+        self.v
+    }
+}
+
+impl PartialEq for Intersection<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.t == other.t && std::ptr::addr_eq(self.object, other.object)
+    }
 }
 
 #[cfg(test)]
@@ -40,6 +61,22 @@ mod tests {
 
         assert_eq!(i.t(), t);
         assert!(std::ptr::eq(i.object(), &s));
+        assert_eq!(i.u(), None);
+        assert_eq!(i.v(), None);
+    }
+
+    #[test]
+    fn with_uv_sets_members() {
+        // Given
+        let s = Sphere::default();
+
+        // When
+        let i = Intersection::with_uv(3.5, &s, 0.2, 0.4);
+
+        // Then
+        assert_eq!(i.t(), 3.5);
+        assert_eq!(i.u(), Some(0.2));
+        assert_eq!(i.v(), Some(0.4));
     }
 
     #[test]
@@ -1,26 +1,366 @@
-use crate::raytracer::{color::Color, tuple::Tuple};
+use crate::raytracer::{color::Color, random::Random, tuple::Tuple, world::World};
+
+/// The way towards a light from the point being shaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightSample {
+    direction: Tuple,
+    distance: f64,
+}
+
+impl LightSample {
+    pub fn new(direction: Tuple, distance: f64) -> LightSample {
+        Self { direction, distance }
+    }
+
+    /// Creates the sample pointing from `point` at `position`.
+    pub fn towards(point: &Tuple, position: &Tuple) -> LightSample {
+        let v = position - point;
+        Self { distance: v.magnitude(), direction: v.normalize() }
+    }
+
+    /// The normalized vector from the point to the light.
+    pub fn direction(&self) -> &Tuple {
+        &self.direction
+    }
+
+    /// How far away the light is, only objects closer than this cast a shadow.
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+}
+
+/// How a light fades with the distance to the point it shines on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Attenuation {
+    None,
+    /// Falls off with `1 / distance`, points within one unit of the light get its full intensity.
+    Linear,
+    /// Falls off with `1 / distance²` like real light, points within one unit of the light get its full intensity.
+    InverseSquare,
+    /// Falls off with `1 / (constant + linear * distance + quadratic * distance²)`.
+    Coefficients { constant: f64, linear: f64, quadratic: f64 },
+}
+
+impl Attenuation {
+    /// The factor the light is scaled by at `distance`, capped at 1 so points right next to the light
+    /// (or coefficients that don't add up to a positive falloff) can't make it brighter than it is.
+    pub fn factor(&self, distance: f64) -> f64 {
+        let (constant, linear, quadratic) = match *self {
+            Attenuation::None => return 1.0,
+            Attenuation::Linear => (0.0, 1.0, 0.0),
+            Attenuation::InverseSquare => (0.0, 0.0, 1.0),
+            Attenuation::Coefficients { constant, linear, quadratic } => (constant, linear, quadratic),
+        };
+        // max also replaces a NaN falloff
+        let falloff = constant + linear * distance + quadratic * distance * distance;
+        1.0 / falloff.max(1.0)
+    }
+}
+
+/// Everything shading needs to know about a light at a point: its color, how it fades, the ways
+/// towards it and the fraction of those that aren't shadowed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Illumination {
+    intensity: Color,
+    attenuation: Attenuation,
+    samples: Vec<LightSample>,
+    visibility: f64,
+}
+
+impl Illumination {
+    pub fn new(intensity: Color, attenuation: Attenuation, samples: Vec<LightSample>, visibility: f64) -> Illumination {
+        Self { intensity, attenuation, samples, visibility }
+    }
+
+    pub fn intensity(&self) -> &Color {
+        &self.intensity
+    }
+
+    pub fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    pub fn samples(&self) -> &[LightSample] {
+        &self.samples
+    }
+
+    /// The fraction of the light reaching the point, see `Light::intensity_at`.
+    pub fn visibility(&self) -> f64 {
+        self.visibility
+    }
+}
+
+pub trait Light: Send + Sync {
+    fn intensity(&self) -> &Color;
+
+    /// How the light fades with distance, lights infinitely far away can't fade.
+    fn attenuation(&self) -> Attenuation {
+        Attenuation::None
+    }
+
+    /// The ways from `point` to the light that shading averages over, lights without an area have a single one.
+    /// Lights that sample themselves at random spots draw them from `rng`.
+    fn samples(&self, point: &Tuple, rng: &mut Random) -> Vec<LightSample>;
+
+    /// The fraction of the light reaching `point`, from 0 when every one of its `samples` is shadowed to 1 when none are.
+    fn intensity_at(&self, point: &Tuple, samples: &[LightSample], world: &World) -> f64 {
+        visible_fraction(point, samples, world)
+    }
+
+    /// Samples the light from `point` and decides how much of it is shadowed, from the same samples
+    /// so jittered area lights agree with themselves.
+    fn illuminate(&self, point: &Tuple, world: &World, rng: &mut Random) -> Illumination {
+        let samples = self.samples(point, rng);
+        let visibility = self.intensity_at(point, &samples, world);
+        Illumination::new(self.intensity().clone(), self.attenuation(), samples, visibility)
+    }
+}
+
+// The fraction of `samples` along which nothing in `world` blocks the way from `point` to the light.
+fn visible_fraction(point: &Tuple, samples: &[LightSample], world: &World) -> f64 {
+    let visible = samples.iter().filter(|sample| !world.is_blocked(point, sample.direction(), sample.distance())).count();
+    visible as f64 / samples.len() as f64
+}
 
 pub struct PointLight {
     position: Tuple,
     intensity: Color,
+    attenuation: Attenuation,
 }
 
 impl PointLight {
     pub fn new(position: Tuple, intensity: Color) -> PointLight {
-        Self { position, intensity }
+        Self { position, intensity, attenuation: Attenuation::None }
     }
 
     pub fn position(&self) -> &Tuple {
         &self.position
     }
 
-    pub fn intensity(&self) -> &Color {
+    pub fn set_attenuation(&mut self, attenuation: Attenuation) {
+        self.attenuation = attenuation;
+    }
+}
+
+impl Light for PointLight {
+    fn intensity(&self) -> &Color {
+        &self.intensity
+    }
+
+    fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    fn samples(&self, point: &Tuple, _rng: &mut Random) -> Vec<LightSample> {
+        vec![LightSample::towards(point, &self.position)]
+    }
+}
+
+/// A light so far away that its rays are parallel, like the sun.
+pub struct DirectionalLight {
+    direction: Tuple,
+    intensity: Color,
+}
+
+impl DirectionalLight {
+    /// Creates a light shining along `direction`.
+    pub fn new(direction: Tuple, intensity: Color) -> DirectionalLight {
+        Self { direction: direction.normalize(), intensity }
+    }
+
+    pub fn direction(&self) -> &Tuple {
+        &self.direction
+    }
+}
+
+impl Light for DirectionalLight {
+    fn intensity(&self) -> &Color {
         &self.intensity
     }
+
+    fn samples(&self, _point: &Tuple, _rng: &mut Random) -> Vec<LightSample> {
+        vec![LightSample::new(-&self.direction, f64::INFINITY)]
+    }
+}
+
+/// A point light that only shines into a cone around `direction`.
+pub struct SpotLight {
+    position: Tuple,
+    direction: Tuple,
+    inner_angle: f64,
+    outer_angle: f64,
+    intensity: Color,
+    attenuation: Attenuation,
+}
+
+impl SpotLight {
+    /// Creates a light at full intensity up to `inner_angle` away from `direction`, fading out towards `outer_angle`.
+    /// The angles are measured from the axis in radians, an outer angle smaller than the inner one is raised to it.
+    pub fn new(position: Tuple, direction: Tuple, inner_angle: f64, outer_angle: f64, intensity: Color) -> SpotLight {
+        Self {
+            position,
+            direction: direction.normalize(),
+            inner_angle,
+            outer_angle: outer_angle.max(inner_angle),
+            intensity,
+            attenuation: Attenuation::None,
+        }
+    }
+
+    pub fn position(&self) -> &Tuple {
+        &self.position
+    }
+
+    pub fn direction(&self) -> &Tuple {
+        &self.direction
+    }
+
+    pub fn inner_angle(&self) -> f64 {
+        self.inner_angle
+    }
+
+    pub fn outer_angle(&self) -> f64 {
+        self.outer_angle
+    }
+
+    pub fn set_attenuation(&mut self, attenuation: Attenuation) {
+        self.attenuation = attenuation;
+    }
+
+    /// How much of the light `point` receives because of its position in the cone, ignoring shadows.
+    pub fn cone_factor(&self, point: &Tuple) -> f64 {
+        let cos_angle = (point - &self.position).normalize().dot(&self.direction);
+        let (cos_inner, cos_outer) = (self.inner_angle.cos(), self.outer_angle.cos());
+        if cos_angle >= cos_inner {
+            return 1.0;
+        }
+        if cos_angle <= cos_outer {
+            return 0.0;
+        }
+
+        // smoothstep between the edges of the cone, which avoids a visible ring where the falloff starts
+        let t = (cos_angle - cos_outer) / (cos_inner - cos_outer);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl Light for SpotLight {
+    fn intensity(&self) -> &Color {
+        &self.intensity
+    }
+
+    fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    fn samples(&self, point: &Tuple, _rng: &mut Random) -> Vec<LightSample> {
+        vec![LightSample::towards(point, &self.position)]
+    }
+
+    /// The visible fraction of `samples`, dimmed by where `point` lies in the cone.
+    fn intensity_at(&self, point: &Tuple, samples: &[LightSample], world: &World) -> f64 {
+        let factor = self.cone_factor(point);
+        if factor == 0.0 {
+            return 0.0;
+        }
+        factor * visible_fraction(point, samples, world)
+    }
+}
+
+/// A rectangular light split into `usteps` x `vsteps` cells, which casts soft shadows.
+pub struct AreaLight {
+    corner: Tuple,
+    uvec: Tuple,
+    usteps: usize,
+    vvec: Tuple,
+    vsteps: usize,
+    position: Tuple,
+    intensity: Color,
+    jitter: bool,
+}
+
+impl AreaLight {
+    /// Creates a light spanning `full_uvec` and `full_vvec` from `corner`, at least one step is used along each edge.
+    pub fn new(corner: Tuple, full_uvec: Tuple, usteps: usize, full_vvec: Tuple, vsteps: usize, intensity: Color) -> AreaLight {
+        let usteps = usteps.max(1);
+        let vsteps = vsteps.max(1);
+        let position = &corner + &(&full_uvec * 0.5) + &(&full_vvec * 0.5);
+
+        Self {
+            corner,
+            uvec: full_uvec / usteps as f64,
+            usteps,
+            vvec: full_vvec / vsteps as f64,
+            vsteps,
+            position,
+            intensity,
+            jitter: false,
+        }
+    }
+
+    pub fn corner(&self) -> &Tuple {
+        &self.corner
+    }
+
+    /// The edge of a single cell along u.
+    pub fn uvec(&self) -> &Tuple {
+        &self.uvec
+    }
+
+    pub fn usteps(&self) -> usize {
+        self.usteps
+    }
+
+    /// The edge of a single cell along v.
+    pub fn vvec(&self) -> &Tuple {
+        &self.vvec
+    }
+
+    pub fn vsteps(&self) -> usize {
+        self.vsteps
+    }
+
+    /// The center of the light.
+    pub fn position(&self) -> &Tuple {
+        &self.position
+    }
+
+    pub fn jitter(&self) -> bool {
+        self.jitter
+    }
+
+    /// Sets whether samples are taken at a random spot in their cell instead of its center,
+    /// which trades the banding of regular samples for noise.
+    pub fn set_jitter(&mut self, jitter: bool) {
+        self.jitter = jitter;
+    }
+
+    /// The point in the cell at column `u` and row `v` the light is sampled at, jittered lights take the spot from `rng`.
+    pub fn point_on_light(&self, u: usize, v: usize, rng: &mut Random) -> Tuple {
+        let (ju, jv) = if self.jitter { (rng.next_f64(), rng.next_f64()) } else { (0.5, 0.5) };
+        &self.corner + &(&self.uvec * (u as f64 + ju)) + &(&self.vvec * (v as f64 + jv))
+    }
+}
+
+impl Light for AreaLight {
+    fn intensity(&self) -> &Color {
+        &self.intensity
+    }
+
+    fn samples(&self, point: &Tuple, rng: &mut Random) -> Vec<LightSample> {
+        (0..self.vsteps)
+            .flat_map(|v| (0..self.usteps).map(move |u| (u, v)))
+            .map(|(u, v)| LightSample::towards(point, &self.point_on_light(u, v, rng)))
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::f64::consts::PI;
+
+    use crate::raytracer::world::tests::default_world;
+
     use super::*;
 
     #[test]
@@ -35,5 +375,270 @@ mod tests {
         // Then
         assert_eq!(light.position(), &position);
         assert_eq!(light.intensity(), &intensity);
+        assert_eq!(light.attenuation(), Attenuation::None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn attenuation_factor_at_distance() {
+        // Given
+        let coefficients = Attenuation::Coefficients { constant: 1.0, linear: 0.5, quadratic: 0.25 };
+
+        // When & Then
+        assert_eq!(Attenuation::None.factor(10.0), 1.0);
+        assert_eq!(Attenuation::Linear.factor(4.0), 0.25);
+        assert_eq!(Attenuation::InverseSquare.factor(4.0), 0.0625);
+        assert_eq!(coefficients.factor(0.0), 1.0);
+        assert_eq!(coefficients.factor(2.0), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn attenuation_factor_never_exceeds_one() {
+        // Given
+        let zero = Attenuation::Coefficients { constant: 0.0, linear: 0.0, quadratic: 0.0 };
+        let negative = Attenuation::Coefficients { constant: -1.0, linear: 0.0, quadratic: 0.0 };
+
+        // When & Then
+        assert_eq!(Attenuation::InverseSquare.factor(0.0), 1.0);
+        assert_eq!(Attenuation::InverseSquare.factor(1e-6), 1.0);
+        assert_eq!(Attenuation::Linear.factor(0.5), 1.0);
+        assert_eq!(Attenuation::Linear.factor(1.0), 1.0);
+        assert_eq!(zero.factor(5.0), 1.0);
+        assert_eq!(negative.factor(5.0), 1.0);
+        assert_eq!(Attenuation::Linear.factor(f64::NAN), 1.0);
+    }
+
+    #[test]
+    fn set_attenuation_changes_attenuation() {
+        // Given
+        let mut point = PointLight::new(Tuple::point(0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0));
+        let mut spot = SpotLight::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, -1.0, 0.0), 0.1, 0.2, Color::new(1.0, 1.0, 1.0));
+
+        // When
+        point.set_attenuation(Attenuation::Linear);
+        spot.set_attenuation(Attenuation::InverseSquare);
+
+        // Then
+        assert_eq!(point.attenuation(), Attenuation::Linear);
+        assert_eq!(spot.attenuation(), Attenuation::InverseSquare);
+    }
+
+    #[test]
+    fn point_light_intensity_at_point() {
+        // Given
+        let w = default_world();
+        let light = &w.lights()[0];
+        let cases = [
+            (Tuple::point(0.0, 1.0001, 0.0), 1.0),
+            (Tuple::point(-1.0001, 0.0, 0.0), 1.0),
+            (Tuple::point(0.0, 0.0, -1.0001), 1.0),
+            (Tuple::point(0.0, 0.0, 1.0001), 0.0),
+            (Tuple::point(1.0001, 0.0, 0.0), 0.0),
+            (Tuple::point(0.0, -1.0001, 0.0), 0.0),
+            (Tuple::point(0.0, 0.0, 0.0), 0.0),
+        ];
+
+        for (point, expected) in cases {
+            // When
+            let intensity = light.intensity_at(&point, &light.samples(&point, &mut Random::new(&[])), &w);
+
+            // Then
+            assert_eq!(intensity, expected, "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn area_light_new_sets_members() {
+        // Given
+        let corner = Tuple::point(0.0, 0.0, 0.0);
+        let v1 = Tuple::vector(2.0, 0.0, 0.0);
+        let v2 = Tuple::vector(0.0, 0.0, 1.0);
+
+        // When
+        let light = AreaLight::new(corner.clone(), v1, 4, v2, 2, Color::new(1.0, 1.0, 1.0));
+
+        // Then
+        assert_eq!(light.corner(), &corner);
+        assert_eq!(light.uvec(), &Tuple::vector(0.5, 0.0, 0.0));
+        assert_eq!(light.usteps(), 4);
+        assert_eq!(light.vvec(), &Tuple::vector(0.0, 0.0, 0.5));
+        assert_eq!(light.vsteps(), 2);
+        assert_eq!(light.samples(&Tuple::point(0.0, 5.0, 0.0), &mut Random::new(&[])).len(), 8);
+        assert_eq!(light.position(), &Tuple::point(1.0, 0.0, 0.5));
+        assert!(!light.jitter());
+    }
+
+    #[test]
+    fn area_light_point_on_light_is_center_of_cell() {
+        // Given
+        let light = AreaLight::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(2.0, 0.0, 0.0), 4, Tuple::vector(0.0, 0.0, 1.0), 2, Color::new(1.0, 1.0, 1.0));
+        let cases = [
+            (0, 0, Tuple::point(0.25, 0.0, 0.25)),
+            (1, 0, Tuple::point(0.75, 0.0, 0.25)),
+            (0, 1, Tuple::point(0.25, 0.0, 0.75)),
+            (2, 0, Tuple::point(1.25, 0.0, 0.25)),
+            (3, 1, Tuple::point(1.75, 0.0, 0.75)),
+        ];
+
+        for (u, v, expected) in cases {
+            // When
+            let point = light.point_on_light(u, v, &mut Random::new(&[]));
+
+            // Then
+            assert_eq!(point, expected);
+        }
+    }
+
+    #[test]
+    fn area_light_jittered_point_stays_in_cell() {
+        // Given
+        let mut light = AreaLight::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(2.0, 0.0, 0.0), 4, Tuple::vector(0.0, 0.0, 1.0), 2, Color::new(1.0, 1.0, 1.0));
+        light.set_jitter(true);
+
+        let mut rng = Random::new(&[7]);
+
+        for _ in 0..100 {
+            // When
+            let point = light.point_on_light(3, 1, &mut rng);
+
+            // Then
+            assert!((1.5..=2.0).contains(&point.x()), "{:?}", point);
+            assert_eq!(point.y(), 0.0);
+            assert!((0.5..=1.0).contains(&point.z()), "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn area_light_intensity_at_point() {
+        // Given
+        let w = default_world();
+        let light = AreaLight::new(Tuple::point(-0.5, -0.5, -5.0), Tuple::vector(1.0, 0.0, 0.0), 2, Tuple::vector(0.0, 1.0, 0.0), 2, Color::new(1.0, 1.0, 1.0));
+        let cases = [
+            (Tuple::point(0.0, 0.0, 2.0), 0.0),
+            (Tuple::point(1.0, -1.0, 2.0), 0.25),
+            (Tuple::point(1.5, 0.0, 2.0), 0.5),
+            (Tuple::point(1.25, 1.25, 3.0), 0.75),
+            (Tuple::point(0.0, 0.0, -2.0), 1.0),
+        ];
+
+        for (point, expected) in cases {
+            // When
+            let intensity = light.intensity_at(&point, &light.samples(&point, &mut Random::new(&[])), &w);
+
+            // Then
+            assert_eq!(intensity, expected, "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn illuminate_combines_samples_and_visibility() {
+        // Given
+        let w = default_world();
+        let mut light = AreaLight::new(Tuple::point(-0.5, -0.5, -5.0), Tuple::vector(1.0, 0.0, 0.0), 2, Tuple::vector(0.0, 1.0, 0.0), 2, Color::new(1.0, 0.5, 1.0));
+        light.set_jitter(true);
+        let point = Tuple::point(1.5, 0.0, 2.0);
+
+        // When
+        let illumination = light.illuminate(&point, &w, &mut Random::new(&[3]));
+
+        // Then
+        assert_eq!(illumination.intensity(), &Color::new(1.0, 0.5, 1.0));
+        assert_eq!(illumination.attenuation(), Attenuation::None);
+        assert_eq!(illumination.samples(), light.samples(&point, &mut Random::new(&[3])).as_slice());
+        assert_eq!(illumination.visibility(), light.intensity_at(&point, illumination.samples(), &w));
+    }
+
+    #[test]
+    fn point_light_sample_points_at_light() {
+        // Given
+        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+
+        // When
+        let samples = light.samples(&Tuple::point(0.0, 0.0, -2.0), &mut Random::new(&[]));
+
+        // Then
+        assert_eq!(samples, vec![LightSample::new(Tuple::vector(0.0, 0.0, -1.0), 8.0)]);
+    }
+
+    #[test]
+    fn directional_light_sample_is_infinitely_far_against_its_direction() {
+        // Given
+        let light = DirectionalLight::new(Tuple::vector(0.0, -2.0, 0.0), Color::new(1.0, 1.0, 1.0));
+
+        // When
+        let samples = light.samples(&Tuple::point(3.0, 0.0, 7.0), &mut Random::new(&[]));
+
+        // Then
+        assert_eq!(light.direction(), &Tuple::vector(0.0, -1.0, 0.0));
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].direction(), &Tuple::vector(0.0, 1.0, 0.0));
+        assert_eq!(samples[0].distance(), f64::INFINITY);
+    }
+
+    #[test]
+    fn directional_light_intensity_at_point() {
+        // Given
+        let w = default_world();
+        let light = DirectionalLight::new(Tuple::vector(0.0, -1.0, 0.0), Color::new(1.0, 1.0, 1.0));
+        let intensity_at = |point: &Tuple| light.intensity_at(point, &light.samples(point, &mut Random::new(&[])), &w);
+
+        // When & Then
+        assert_eq!(intensity_at(&Tuple::point(0.0, -1.0001, 0.0)), 0.0);
+        assert_eq!(intensity_at(&Tuple::point(0.0, -100.0, 0.0)), 0.0);
+        assert_eq!(intensity_at(&Tuple::point(1.5, -1.0, 0.0)), 1.0);
+    }
+
+    #[test]
+    fn spot_light_new_raises_outer_angle() {
+        // When
+        let light = SpotLight::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 2.0), 0.5, 0.25, Color::new(1.0, 1.0, 1.0));
+
+        // Then
+        assert_eq!(light.direction(), &Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(light.inner_angle(), 0.5);
+        assert_eq!(light.outer_angle(), 0.5);
+    }
+
+    #[test]
+    fn spot_light_cone_factor_falls_off_smoothly() {
+        // Given
+        let light = SpotLight::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, -1.0, 0.0), PI / 8.0, PI / 4.0, Color::new(1.0, 1.0, 1.0));
+        let at_angle = |angle: f64| Tuple::point(angle.sin(), -angle.cos(), 0.0);
+
+        // When & Then
+        assert_eq!(light.cone_factor(&at_angle(0.0)), 1.0);
+        assert_eq!(light.cone_factor(&at_angle(PI / 10.0)), 1.0);
+        assert_eq!(light.cone_factor(&at_angle(PI / 4.0 + 0.01)), 0.0);
+        assert_eq!(light.cone_factor(&at_angle(PI / 2.0)), 0.0);
+        let halfway = light.cone_factor(&at_angle(3.0 * PI / 16.0));
+        assert!(halfway > 0.0 && halfway < 1.0, "{}", halfway);
+        assert!(light.cone_factor(&at_angle(0.4)) > light.cone_factor(&at_angle(0.6)));
+    }
+
+    #[test]
+    fn spot_light_intensity_at_point() {
+        // Given
+        let w = default_world();
+        let light = SpotLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0), PI / 8.0, PI / 6.0, Color::new(1.0, 1.0, 1.0));
+        let intensity_at = |point: &Tuple| light.intensity_at(point, &light.samples(point, &mut Random::new(&[])), &w);
+
+        // When & Then
+        assert_eq!(intensity_at(&Tuple::point(0.0, 0.0, -1.0001)), 1.0);
+        assert_eq!(intensity_at(&Tuple::point(0.0, 0.0, 1.0001)), 0.0);
+        assert_eq!(intensity_at(&Tuple::point(0.0, 10.0, -1.0)), 0.0);
+    }
+
+    #[test]
+    fn spot_light_intensity_at_uses_given_samples() {
+        // Given
+        let w = default_world();
+        let light = SpotLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0), PI / 8.0, PI / 6.0, Color::new(1.0, 1.0, 1.0));
+        // behind the spheres, so the way straight to the light is blocked
+        let point = Tuple::point(0.0, 0.0, 1.0001);
+        let samples = [LightSample::new(Tuple::vector(0.0, 1.0, 0.0), 5.0), LightSample::towards(&point, light.position())];
+
+        // When & Then
+        assert_eq!(light.intensity_at(&point, &samples[..1], &w), 1.0);
+        assert_eq!(light.intensity_at(&point, &samples[1..], &w), 0.0);
+        assert_eq!(light.intensity_at(&point, &samples, &w), 0.5);
+    }
+}
@@ -2,7 +2,7 @@ use float_cmp::approx_eq;
 
 use crate::raytracer::{color::Color, tuple::Tuple};
 
-use super::lights::PointLight;
+use super::{lights::Illumination, object::Object, patterns::Pattern};
 
 #[derive(Debug, Clone)]
 pub struct Material {
@@ -11,11 +11,15 @@ pub struct Material {
     diffuse: f64,
     specular: f64,
     shininess: f64,
+    reflective: f64,
+    transparency: f64,
+    refractive_index: f64,
+    pattern: Option<Box<dyn Pattern>>,
 }
 
 impl Material {
     pub fn new(color: Color, ambient: f64, diffuse: f64, specular: f64, shininess: f64) -> Material {
-        Self { color, ambient, diffuse, specular, shininess }
+        Self { color, ambient, diffuse, specular, shininess, reflective: 0.0, transparency: 0.0, refractive_index: 1.0, pattern: None }
     }
 
     pub fn color(&self) -> &Color {
@@ -58,26 +62,78 @@ impl Material {
         self.shininess = shininess;
     }
 
-    pub fn lighting(&self, light: &PointLight, position: &Tuple, eye_vec: &Tuple, normal_vec: &Tuple) -> Color {
-        // combine the surface color with the light's color/intensity
-        let effective_color = &self.color * light.intensity();
+    pub fn reflective(&self) -> f64 {
+        self.reflective
+    }
+
+    pub fn set_reflective(&mut self, reflective: f64) {
+        self.reflective = reflective;
+    }
+
+    pub fn transparency(&self) -> f64 {
+        self.transparency
+    }
+
+    pub fn set_transparency(&mut self, transparency: f64) {
+        self.transparency = transparency;
+    }
+
+    pub fn refractive_index(&self) -> f64 {
+        self.refractive_index
+    }
 
-        // find the direction to the light source
-        let lightv = (light.position() - position).normalize();
+    pub fn set_refractive_index(&mut self, refractive_index: f64) {
+        self.refractive_index = refractive_index;
+    }
+
+    pub fn pattern(&self) -> Option<&dyn Pattern> {
+        self.pattern.as_deref()
+    }
+
+    pub fn set_pattern(&mut self, pattern: Box<dyn Pattern>) {
+        self.pattern = Some(pattern);
+    }
+
+    /// Shades `position` lit by a light as described by `illumination` (see `Light::illuminate`).
+    pub fn lighting(&self, object: &dyn Object, illumination: &Illumination, position: &Tuple, eye_vec: &Tuple, normal_vec: &Tuple) -> Color {
+        // a pattern replaces the flat surface color
+        let color = match &self.pattern {
+            Some(pattern) => pattern.pattern_at_object(object, position),
+            None => self.color.clone(),
+        };
+
+        // combine the surface color with the light's color/intensity
+        let effective_color = &color * illumination.intensity();
 
         // compute the ambient contribution
         let ambient = &effective_color * self.ambient;
 
-        // light_dot_normal represents the cosine of the angle between the light vector and the normal vector. 
-        // A negative number means the light is on the other side of the surface.
-        let light_dot_normal = lightv.dot(normal_vec);
+        // a point in shadow only receives the ambient contribution
+        let intensity = illumination.visibility();
+        if intensity == 0.0 {
+            return ambient;
+        }
+
+        // area lights shine from many points, each of them adds its share of the diffuse and specular light
+        let attenuation = illumination.attenuation();
+        let samples = illumination.samples();
+        let mut sum = Color::new(0.0, 0.0, 0.0);
+        for sample in samples.iter() {
+            // find the direction to the light source
+            let lightv = sample.direction().clone();
+
+            // light_dot_normal represents the cosine of the angle between the light vector and the normal vector. 
+            // A negative number means the light is on the other side of the surface.
+            let light_dot_normal = lightv.dot(normal_vec);
+            if light_dot_normal < 0.0 {
+                continue;
+            }
 
-        let mut diffuse = Color::new(0.0, 0.0, 0.0);
-        let mut specular = Color::new(0.0, 0.0, 0.0);
+            // the light fades on its way to the surface
+            let fade = attenuation.factor(sample.distance());
 
-        if light_dot_normal >= 0.0 {
             // compute the diffuse contribution
-            diffuse = &effective_color * self.diffuse * light_dot_normal;
+            sum = sum + &(&effective_color * self.diffuse * light_dot_normal * fade);
 
             // reflect_dot_eye represents the cosine of the angle between the reflection vector and the eye vector. 
             // A negative number means the light reflects away from the eye.
@@ -87,12 +143,12 @@ impl Material {
             if reflect_dot_eye > 0.0 {
                 // compute the specular contribution
                 let factor = reflect_dot_eye.powf(self.shininess);
-                specular = light.intensity() * self.specular * factor;
+                sum = sum + &(illumination.intensity() * self.specular * factor * fade);
             }
         }
 
-        // add the three contributions together to get the final shading
-        &ambient + &diffuse + &specular
+        // add the ambient light to the average of the samples, dimmed by how much of the light is visible
+        &ambient + &(sum * (intensity / samples.len() as f64))
     }
 
 
@@ -106,6 +162,10 @@ impl Default for Material {
             diffuse: 0.9,
             specular: 0.9,
             shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
+            pattern: None,
         }
     }
 }
@@ -116,7 +176,19 @@ impl PartialEq for Material {
         approx_eq!(f64, self.diffuse, other.diffuse, epsilon = 0.00001) &&
         approx_eq!(f64, self.specular, other.specular, epsilon = 0.00001) &&
         approx_eq!(f64, self.shininess, other.shininess, epsilon = 0.00001) &&
-        self.color == other.color
+        approx_eq!(f64, self.reflective, other.reflective, epsilon = 0.00001) &&
+        approx_eq!(f64, self.transparency, other.transparency, epsilon = 0.00001) &&
+        approx_eq!(f64, self.refractive_index, other.refractive_index, epsilon = 0.00001) &&
+        self.color == other.color &&
+        same_pattern(&self.pattern, &other.pattern)
+    }
+}
+
+fn same_pattern(a: &Option<Box<dyn Pattern>>, b: &Option<Box<dyn Pattern>>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => a.eq_pattern(b.as_ref()),
+        _ => false,
     }
 }
 
@@ -128,10 +200,16 @@ impl PartialEq<Material> for &Material {
 
 #[cfg(test)]
 mod tests {
-    use crate::raytracer::{objects::lights::PointLight, tuple::Tuple};
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    use crate::raytracer::{objects::{lights::{AreaLight, Attenuation, DirectionalLight, Light, PointLight}, patterns::{GradientPattern, StripePattern}, sphere::Sphere}, random::Random, transformation, tuple::Tuple};
 
     use super::*;
 
+    fn illumination(light: &dyn Light, point: &Tuple, visibility: f64) -> Illumination {
+        Illumination::new(light.intensity().clone(), light.attenuation(), light.samples(point, &mut Random::new(&[])), visibility)
+    }
+
     #[test]
     fn new_sets_members() {
         // Given
@@ -152,6 +230,49 @@ mod tests {
         assert_eq!(m.color(), &color);
     }
 
+    #[test]
+    fn default_is_not_reflective() {
+        // When
+        let m = Material::default();
+
+        // Then
+        assert_eq!(m.reflective(), 0.0);
+    }
+
+    #[test]
+    fn default_is_opaque_with_refractive_index_of_vacuum() {
+        // When
+        let m = Material::default();
+
+        // Then
+        assert_eq!(m.transparency(), 0.0);
+        assert_eq!(m.refractive_index(), 1.0);
+    }
+
+    #[test]
+    fn eq_compares_patterns() {
+        // Given
+        let white = Color::new(1.0, 1.0, 1.0);
+        let black = Color::new(0.0, 0.0, 0.0);
+        let mut striped = Material::default();
+        striped.set_pattern(Box::new(StripePattern::new(white.clone(), black.clone())));
+        let mut moved = striped.clone();
+        let mut stripes = StripePattern::new(white.clone(), black.clone());
+        stripes.set_transform(transformation::translation(0.5, 0.0, 0.0));
+        moved.set_pattern(Box::new(stripes));
+        let mut gradient = Material::default();
+        gradient.set_pattern(Box::new(GradientPattern::new(white, black)));
+
+        // When & Then
+        assert_eq!(striped, striped.clone());
+        assert_ne!(striped, Material::default());
+        assert_ne!(striped, moved);
+        assert_ne!(striped, gradient);
+        let mut nearly = Material::default();
+        nearly.set_pattern(Box::new(StripePattern::new(Color::new(1.0, 1.0, 0.999999), Color::new(0.0, 0.0, 0.0))));
+        assert_eq!(striped, nearly);
+    }
+
     #[test]
     fn lighnting_with_eye_between_light_and_surface() {
         // Given
@@ -162,7 +283,7 @@ mod tests {
         let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
 
         // When
-        let result = m.lighting(&light, &position, &eyev, &normalv);
+        let result = m.lighting(&Sphere::default(), &illumination(&light, &position, 1.0), &position, &eyev, &normalv);
 
         // Then
         assert_eq!(result, Color::new(1.9, 1.9, 1.9));
@@ -178,7 +299,7 @@ mod tests {
         let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
 
         // When
-        let result = m.lighting(&light, &position, &eyev, &normalv);
+        let result = m.lighting(&Sphere::default(), &illumination(&light, &position, 1.0), &position, &eyev, &normalv);
 
         // Then
         assert_eq!(result, Color::new(1.0, 1.0, 1.0));
@@ -194,7 +315,7 @@ mod tests {
     let light = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
 
         // When
-        let result = m.lighting(&light, &position, &eyev, &normalv);
+        let result = m.lighting(&Sphere::default(), &illumination(&light, &position, 1.0), &position, &eyev, &normalv);
 
         // Then
         assert_eq!(result, Color::new(0.7364, 0.7364, 0.7364));
@@ -210,7 +331,7 @@ mod tests {
         let light = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
 
         // When
-        let result = m.lighting(&light, &position, &eyev, &normalv);
+        let result = m.lighting(&Sphere::default(), &illumination(&light, &position, 1.0), &position, &eyev, &normalv);
 
         // Then
         assert_eq!(result, Color::new(1.6364, 1.6364, 1.6364));
@@ -226,10 +347,129 @@ mod tests {
         let light = PointLight::new(Tuple::point(0.0, 0.0, 10.0), Color::new(1.0, 1.0, 1.0));
 
         // When
-        let result = m.lighting(&light, &position, &eyev, &normalv);
+        let result = m.lighting(&Sphere::default(), &illumination(&light, &position, 1.0), &position, &eyev, &normalv);
 
         // Then
         assert_eq!(result, Color::new(0.1, 0.1, 0.1));
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn lighting_with_directional_light_behind_eye() {
+        // Given
+        let m = Material::default();
+        let position = Tuple::point(0.0, 0.0, 0.0);
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = DirectionalLight::new(Tuple::vector(0.0, 0.0, 1.0), Color::new(1.0, 1.0, 1.0));
+
+        // When
+        let result = m.lighting(&Sphere::default(), &illumination(&light, &position, 1.0), &position, &eyev, &normalv);
+
+        // Then
+        assert_eq!(result, Color::new(1.9, 1.9, 1.9));
+    }
+
+    #[test]
+    fn lighting_with_attenuated_light() {
+        // Given
+        let m = Material::default();
+        let position = Tuple::point(0.0, 0.0, 0.0);
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let mut light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let cases = [
+            (Attenuation::None, Color::new(1.9, 1.9, 1.9)),
+            (Attenuation::Linear, Color::new(0.28, 0.28, 0.28)),
+            (Attenuation::InverseSquare, Color::new(0.118, 0.118, 0.118)),
+            (Attenuation::Coefficients { constant: 1.0, linear: 0.0, quadratic: 0.08 }, Color::new(0.3, 0.3, 0.3)),
+        ];
+
+        for (attenuation, expected) in cases {
+            light.set_attenuation(attenuation);
+
+            // When
+            let result = m.lighting(&Sphere::default(), &illumination(&light, &position, 1.0), &position, &eyev, &normalv);
+
+            // Then
+            assert_eq!(result, expected, "{:?}", attenuation);
+        }
+    }
+
+    #[test]
+    fn lighting_with_surface_in_shadow() {
+        // Given
+        let m = Material::default();
+        let position = Tuple::point(0.0, 0.0, 0.0);
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+
+        // When
+        let result = m.lighting(&Sphere::default(), &illumination(&light, &position, 0.0), &position, &eyev, &normalv);
+
+        // Then
+        assert_eq!(result, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn lighting_with_pattern_applied() {
+        // Given
+        let mut m = Material::new(Color::new(1.0, 1.0, 1.0), 1.0, 0.0, 0.0, 200.0);
+        m.set_pattern(Box::new(StripePattern::new(Color::new(1.0, 1.0, 1.0), Color::new(0.0, 0.0, 0.0))));
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let object = Sphere::default();
+
+        // When
+        let c1 = m.lighting(&object, &illumination(&light, &Tuple::point(0.9, 0.0, 0.0), 1.0), &Tuple::point(0.9, 0.0, 0.0), &eyev, &normalv);
+        let c2 = m.lighting(&object, &illumination(&light, &Tuple::point(1.1, 0.0, 0.0), 1.0), &Tuple::point(1.1, 0.0, 0.0), &eyev, &normalv);
+
+        // Then
+        assert_eq!(c1, Color::new(1.0, 1.0, 1.0));
+        assert_eq!(c2, Color::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn lighting_uses_light_intensity_to_attenuate_color() {
+        // Given
+        let m = Material::new(Color::new(1.0, 1.0, 1.0), 0.1, 0.9, 0.0, 200.0);
+        let position = Tuple::point(0.0, 0.0, -1.0);
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let cases = [(1.0, Color::new(1.0, 1.0, 1.0)), (0.5, Color::new(0.55, 0.55, 0.55)), (0.0, Color::new(0.1, 0.1, 0.1))];
+
+        for (intensity, expected) in cases {
+            // When
+            let result = m.lighting(&Sphere::default(), &illumination(&light, &position, intensity), &position, &eyev, &normalv);
+
+            // Then
+            assert_eq!(result, expected, "intensity {}", intensity);
+        }
+    }
+
+    #[test]
+    fn lighting_samples_area_light() {
+        // Given
+        let corner = Tuple::point(-0.5, -0.5, -5.0);
+        let light = AreaLight::new(corner, Tuple::vector(1.0, 0.0, 0.0), 2, Tuple::vector(0.0, 1.0, 0.0), 2, Color::new(1.0, 1.0, 1.0));
+        let m = Material::new(Color::new(1.0, 1.0, 1.0), 0.1, 0.9, 0.0, 200.0);
+        let eye = Tuple::point(0.0, 0.0, -5.0);
+        let cases = [
+            (Tuple::point(0.0, 0.0, -1.0), Color::new(0.9965, 0.9965, 0.9965)),
+            (Tuple::point(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2), Color::new(0.62318, 0.62318, 0.62318)),
+        ];
+
+        for (point, expected) in cases {
+            let eyev = (&eye - &point).normalize();
+            let normalv = Tuple::vector(point.x(), point.y(), point.z());
+
+            // When
+            let result = m.lighting(&Sphere::default(), &illumination(&light, &point, 1.0), &point, &eyev, &normalv);
+
+            // Then
+            assert_eq!(result, expected, "{:?}", point);
+        }
+    }
+}
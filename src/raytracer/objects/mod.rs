@@ -1,5 +1,17 @@
 pub mod object;
+pub mod transform;
+pub mod bounds;
 pub mod sphere;
+pub mod plane;
+pub mod cube;
+pub mod cylinder;
+pub mod cone;
+pub mod group;
+pub mod triangle;
+pub mod smooth_triangle;
+pub mod csg;
 pub mod intersection;
+pub mod computations;
 pub mod lights;
 pub mod materials;
+pub mod patterns;
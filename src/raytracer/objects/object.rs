@@ -1,13 +1,98 @@
 use crate::raytracer::{matrix::Matrix, ray::Ray, tuple::Tuple};
 
+use super::bounds::BoundingBox;
 use super::intersection::Intersection;
 use super::materials::Material;
+use super::transform::{Transform, Transforms};
 
-pub trait Object { 
-    fn intersects(&self, ray: &Ray) -> Vec<Intersection>;
-    fn normal_at(&self, point: &Tuple) -> Tuple;
-    fn transform(&self) -> &Matrix;
-    fn set_transform(&mut self, transform: Matrix);
+/// Objects are shared between the render threads, so they have to be `Send` and `Sync`.
+pub trait Object: Send + Sync {
+    fn intersects(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let local_ray = ray.transform(self.transform_inverse());
+        self.local_intersect(&local_ray)
+    }
+
+    fn normal_at(&self, point: &Tuple) -> Tuple {
+        let local_point = self.world_to_object(point);
+        let local_normal = self.local_normal_at(&local_point);
+        self.normal_to_world(&local_normal)
+    }
+
+    /// Like `normal_at`, but lets shapes use additional information of the intersection, e.g. to interpolate normals.
+    fn normal_at_hit(&self, point: &Tuple, hit: &Intersection) -> Tuple {
+        let local_point = self.world_to_object(point);
+        let local_normal = self.local_normal_at_hit(&local_point, hit);
+        self.normal_to_world(&local_normal)
+    }
+
+    /// Computes the normal for a point given in object space, falling back to `local_normal_at` by default.
+    fn local_normal_at_hit(&self, point: &Tuple, _hit: &Intersection) -> Tuple {
+        self.local_normal_at(point)
+    }
+
+    /// Converts a world space point into object space by undoing the transforms of all parent groups and the object itself.
+    fn world_to_object(&self, point: &Tuple) -> Tuple {
+        self.transform_inverse() * &(self.parent_transform_inverse() * point)
+    }
+
+    /// Converts an object space normal into world space by applying the transforms of the object and all parent groups.
+    fn normal_to_world(&self, normal: &Tuple) -> Tuple {
+        let world_inverse = self.transform_inverse() * self.parent_transform_inverse();
+        let world_normal = &world_inverse.transpose() * normal;
+        let world_normal = Tuple::vector(world_normal.x(), world_normal.y(), world_normal.z());
+        world_normal.normalize()
+    }
+
+    /// Whether `other` is this object or, for composite objects, one of their descendants.
+    fn includes(&self, other: &dyn Object) -> bool {
+        std::ptr::addr_eq(self, other)
+    }
+
+    /// The box around the object in the space of its parent, i.e. with its own transform applied.
+    fn bounds(&self) -> BoundingBox {
+        self.local_bounds().transform(self.transform())
+    }
+
+    /// Splits composite objects with more than `threshold` children into a hierarchy of smaller groups
+    /// by their bounds, so rays only need to be tested against the parts they can actually hit.
+    fn divide(&mut self, _threshold: usize) {}
+
+    /// Intersects a ray that has already been transformed into object space.
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>>;
+    /// Computes the normal for a point given in object space.
+    fn local_normal_at(&self, point: &Tuple) -> Tuple;
+    /// The box around the object in object space.
+    fn local_bounds(&self) -> BoundingBox;
+    /// The transforms of the object, every method below that deals with transforms goes through them.
+    fn transforms(&self) -> &Transforms;
+    fn transforms_mut(&mut self) -> &mut Transforms;
     fn material(&self) -> &Material;
     fn set_material(&mut self, material: Material);
+
+    fn transform(&self) -> &Matrix {
+        self.transforms().own().matrix()
+    }
+
+    fn transform_inverse(&self) -> &Matrix {
+        self.transforms().own().inverse()
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.transforms_mut().set_own(transform);
+    }
+
+    /// The combined transform of all groups containing the object, identity if it isn't part of a group.
+    fn parent_transform(&self) -> &Matrix {
+        self.transforms().parent().matrix()
+    }
+
+    fn parent_transform_inverse(&self) -> &Matrix {
+        self.transforms().parent().inverse()
+    }
+
+    /// Sets the combined transform of all parent groups, whose inverse the parents compose from the
+    /// inverses they already cache, so nothing is inverted again when hierarchies are built.
+    fn set_parent_transform(&mut self, parent_transform: Transform) {
+        self.transforms_mut().set_parent(parent_transform);
+    }
 }
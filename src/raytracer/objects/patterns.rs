@@ -0,0 +1,429 @@
+use std::{any::Any, fmt::Debug};
+
+use crate::raytracer::{color::Color, matrix::Matrix, tuple::Tuple};
+
+use super::{object::Object, transform::Transform};
+
+pub trait Pattern: Debug + Send + Sync {
+    /// Returns the color for a point given in pattern space.
+    fn pattern_at(&self, point: &Tuple) -> Color;
+    /// The transform of the pattern, the transform methods below go through it.
+    fn cached_transform(&self) -> &Transform;
+    fn cached_transform_mut(&mut self) -> &mut Transform;
+    fn box_clone(&self) -> Box<dyn Pattern>;
+    fn as_any(&self) -> &dyn Any;
+    /// Whether `other` is the same kind of pattern with (approximately) the same colors and transform.
+    fn eq_pattern(&self, other: &dyn Pattern) -> bool;
+
+    fn transform(&self) -> &Matrix {
+        self.cached_transform().matrix()
+    }
+
+    fn transform_inverse(&self) -> &Matrix {
+        self.cached_transform().inverse()
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        *self.cached_transform_mut() = Transform::new(transform);
+    }
+
+    /// Converts a world space point into object space and then into pattern space before sampling the pattern.
+    fn pattern_at_object(&self, object: &dyn Object, world_point: &Tuple) -> Color {
+        let object_point = object.world_to_object(world_point);
+        let pattern_point = self.transform_inverse() * &object_point;
+        self.pattern_at(&pattern_point)
+    }
+}
+
+// Implements `Pattern::eq_pattern` for patterns that can compare themselves to their own kind.
+fn eq_same_kind<P: Pattern + PartialEq + 'static>(pattern: &P, other: &dyn Pattern) -> bool {
+    other.as_any().downcast_ref::<P>().is_some_and(|other| pattern == other)
+}
+
+impl Clone for Box<dyn Pattern> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+/// Alternates between two colors along the x axis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StripePattern {
+    a: Color,
+    b: Color,
+    transform: Transform,
+}
+
+impl StripePattern {
+    pub fn new(a: Color, b: Color) -> StripePattern {
+        Self { a, b, transform: Transform::default() }
+    }
+
+    pub fn a(&self) -> &Color {
+        &self.a
+    }
+
+    pub fn b(&self) -> &Color {
+        &self.b
+    }
+}
+
+impl Pattern for StripePattern {
+    fn pattern_at(&self, point: &Tuple) -> Color {
+        if point.x().floor() % 2.0 == 0.0 {
+            self.a.clone()
+        } else {
+            self.b.clone()
+        }
+    }
+
+    fn cached_transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn cached_transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn box_clone(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_pattern(&self, other: &dyn Pattern) -> bool {
+        eq_same_kind(self, other)
+    }
+}
+
+/// Linearly blends from one color to the other along the x axis, repeating every unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientPattern {
+    a: Color,
+    b: Color,
+    transform: Transform,
+}
+
+impl GradientPattern {
+    pub fn new(a: Color, b: Color) -> GradientPattern {
+        Self { a, b, transform: Transform::default() }
+    }
+}
+
+impl Pattern for GradientPattern {
+    fn pattern_at(&self, point: &Tuple) -> Color {
+        let distance = &self.b - &self.a;
+        let fraction = point.x() - point.x().floor();
+        &self.a + distance * fraction
+    }
+
+    fn cached_transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn cached_transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn box_clone(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_pattern(&self, other: &dyn Pattern) -> bool {
+        eq_same_kind(self, other)
+    }
+}
+
+/// Concentric rings around the y axis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RingPattern {
+    a: Color,
+    b: Color,
+    transform: Transform,
+}
+
+impl RingPattern {
+    pub fn new(a: Color, b: Color) -> RingPattern {
+        Self { a, b, transform: Transform::default() }
+    }
+}
+
+impl Pattern for RingPattern {
+    fn pattern_at(&self, point: &Tuple) -> Color {
+        let distance = (point.x().powi(2) + point.z().powi(2)).sqrt();
+        if distance.floor() % 2.0 == 0.0 {
+            self.a.clone()
+        } else {
+            self.b.clone()
+        }
+    }
+
+    fn cached_transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn cached_transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn box_clone(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_pattern(&self, other: &dyn Pattern) -> bool {
+        eq_same_kind(self, other)
+    }
+}
+
+/// Alternating unit cubes in all three dimensions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckersPattern {
+    a: Color,
+    b: Color,
+    transform: Transform,
+}
+
+impl CheckersPattern {
+    pub fn new(a: Color, b: Color) -> CheckersPattern {
+        Self { a, b, transform: Transform::default() }
+    }
+}
+
+impl Pattern for CheckersPattern {
+    fn pattern_at(&self, point: &Tuple) -> Color {
+        let sum = point.x().floor() + point.y().floor() + point.z().floor();
+        if sum % 2.0 == 0.0 {
+            self.a.clone()
+        } else {
+            self.b.clone()
+        }
+    }
+
+    fn cached_transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn cached_transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn box_clone(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_pattern(&self, other: &dyn Pattern) -> bool {
+        eq_same_kind(self, other)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::raytracer::{objects::sphere::Sphere, transformation};
+
+    use super::*;
+
+    fn white() -> Color {
+        Color::new(1.0, 1.0, 1.0)
+    }
+
+    fn black() -> Color {
+        Color::new(0.0, 0.0, 0.0)
+    }
+
+    // Returns the pattern space point as color, so the applied transformations can be inspected.
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub(crate) struct TestPattern {
+        transform: Transform,
+    }
+
+    impl Pattern for TestPattern {
+        fn pattern_at(&self, point: &Tuple) -> Color {
+            Color::new(point.x(), point.y(), point.z())
+        }
+
+        fn cached_transform(&self) -> &Transform {
+            &self.transform
+        }
+
+        fn cached_transform_mut(&mut self) -> &mut Transform {
+            &mut self.transform
+        }
+
+        fn box_clone(&self) -> Box<dyn Pattern> {
+            Box::new(self.clone())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn eq_pattern(&self, other: &dyn Pattern) -> bool {
+            eq_same_kind(self, other)
+        }
+    }
+
+    #[test]
+    fn stripe_new_sets_members() {
+        // When
+        let p = StripePattern::new(white(), black());
+
+        // Then
+        assert_eq!(p.a(), &white());
+        assert_eq!(p.b(), &black());
+        assert_eq!(p.transform(), &Matrix::identity(4, 4));
+    }
+
+    #[test]
+    fn stripe_is_constant_in_y_and_z() {
+        // Given
+        let p = StripePattern::new(white(), black());
+
+        // When & Then
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 1.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 2.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 1.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 2.0)), white());
+    }
+
+    #[test]
+    fn stripe_alternates_in_x() {
+        // Given
+        let p = StripePattern::new(white(), black());
+
+        // When & Then
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.9, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(1.0, 0.0, 0.0)), black());
+        assert_eq!(p.pattern_at(&Tuple::point(-0.1, 0.0, 0.0)), black());
+        assert_eq!(p.pattern_at(&Tuple::point(-1.0, 0.0, 0.0)), black());
+        assert_eq!(p.pattern_at(&Tuple::point(-1.1, 0.0, 0.0)), white());
+    }
+
+    #[test]
+    fn set_transform_caches_inverse() {
+        // Given
+        let mut p = StripePattern::new(white(), black());
+
+        // When
+        p.set_transform(transformation::scaling(2.0, 4.0, 5.0));
+
+        // Then
+        assert_eq!(p.transform_inverse(), &transformation::scaling(0.5, 0.25, 0.2));
+    }
+
+    #[test]
+    fn pattern_at_object_applies_object_transform() {
+        // Given
+        let mut s = Sphere::default();
+        s.set_transform(transformation::scaling(2.0, 2.0, 2.0));
+        let p = TestPattern::default();
+
+        // When
+        let c = p.pattern_at_object(&s, &Tuple::point(2.0, 3.0, 4.0));
+
+        // Then
+        assert_eq!(c, Color::new(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn pattern_at_object_applies_pattern_transform() {
+        // Given
+        let s = Sphere::default();
+        let mut p = TestPattern::default();
+        p.set_transform(transformation::scaling(2.0, 2.0, 2.0));
+
+        // When
+        let c = p.pattern_at_object(&s, &Tuple::point(2.0, 3.0, 4.0));
+
+        // Then
+        assert_eq!(c, Color::new(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn pattern_at_object_applies_object_and_pattern_transform() {
+        // Given
+        let mut s = Sphere::default();
+        s.set_transform(transformation::scaling(2.0, 2.0, 2.0));
+        let mut p = TestPattern::default();
+        p.set_transform(transformation::translation(0.5, 1.0, 1.5));
+
+        // When
+        let c = p.pattern_at_object(&s, &Tuple::point(2.5, 3.0, 3.5));
+
+        // Then
+        assert_eq!(c, Color::new(0.75, 0.5, 0.25));
+    }
+
+    #[test]
+    fn gradient_interpolates_between_colors() {
+        // Given
+        let p = GradientPattern::new(white(), black());
+
+        // When & Then
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.25, 0.0, 0.0)), Color::new(0.75, 0.75, 0.75));
+        assert_eq!(p.pattern_at(&Tuple::point(0.5, 0.0, 0.0)), Color::new(0.5, 0.5, 0.5));
+        assert_eq!(p.pattern_at(&Tuple::point(0.75, 0.0, 0.0)), Color::new(0.25, 0.25, 0.25));
+    }
+
+    #[test]
+    fn ring_extends_in_x_and_z() {
+        // Given
+        let p = RingPattern::new(white(), black());
+
+        // When & Then
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(1.0, 0.0, 0.0)), black());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 1.0)), black());
+        // 0.708 = just slightly more than sqrt(2)/2
+        assert_eq!(p.pattern_at(&Tuple::point(0.708, 0.0, 0.708)), black());
+    }
+
+    #[test]
+    fn checkers_repeat_in_x() {
+        // Given
+        let p = CheckersPattern::new(white(), black());
+
+        // When & Then
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.99, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(1.01, 0.0, 0.0)), black());
+    }
+
+    #[test]
+    fn checkers_repeat_in_y() {
+        // Given
+        let p = CheckersPattern::new(white(), black());
+
+        // When & Then
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.99, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 1.01, 0.0)), black());
+    }
+
+    #[test]
+    fn checkers_repeat_in_z() {
+        // Given
+        let p = CheckersPattern::new(white(), black());
+
+        // When & Then
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.99)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 1.01)), black());
+    }
+}
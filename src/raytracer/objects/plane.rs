@@ -0,0 +1,180 @@
+use crate::raytracer::{ray::Ray, tuple::Tuple, EPSILON};
+
+use super::{bounds::BoundingBox, intersection::Intersection, materials::Material, object::Object, transform::Transforms};
+
+/// An infinite plane spanning x and z in object space.
+#[derive(Default)]
+pub struct Plane {
+    transforms: Transforms,
+    material: Material
+}
+
+impl Object for Plane {
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        // a ray parallel to (or lying in) the plane never hits it
+        if ray.direction().y().abs() < EPSILON {
+            return vec![];
+        }
+
+        let t = -ray.origin().y() / ray.direction().y();
+        vec![Intersection::new(t, self)]
+    }
+
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        Tuple::vector(0.0, 1.0, 0.0)
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        BoundingBox::new(Tuple::point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY), Tuple::point(f64::INFINITY, 0.0, f64::INFINITY))
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut Transforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::raytracer::{color::Color, transformation};
+
+    use super::*;
+
+    #[test]
+    fn normal_is_constant_everywhere() {
+        // Given
+        let p = Plane::default();
+
+        // When
+        let n1 = p.local_normal_at(&Tuple::point(0.0, 0.0, 0.0));
+        let n2 = p.local_normal_at(&Tuple::point(10.0, 0.0, -10.0));
+        let n3 = p.local_normal_at(&Tuple::point(-5.0, 0.0, 150.0));
+
+        // Then
+        assert_eq!(n1, Tuple::vector(0.0, 1.0, 0.0));
+        assert_eq!(n2, Tuple::vector(0.0, 1.0, 0.0));
+        assert_eq!(n3, Tuple::vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn intersects_ray_parallel_to_plane() {
+        // Given
+        let p = Plane::default();
+        let r = Ray::new(Tuple::point(0.0, 10.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = p.intersects(&r);
+
+        // Then
+        assert_eq!(xs.len(), 0);
+    }
+
+    #[test]
+    fn intersects_coplanar_ray() {
+        // Given
+        let p = Plane::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = p.intersects(&r);
+
+        // Then
+        assert_eq!(xs.len(), 0);
+    }
+
+    #[test]
+    fn intersects_ray_from_above() {
+        // Given
+        let p = Plane::default();
+        let r = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+        // When
+        let xs = p.intersects(&r);
+
+        // Then
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t(), 1.0);
+        assert!(std::ptr::addr_eq(xs[0].object(), &p));
+    }
+
+    #[test]
+    fn intersects_ray_from_below() {
+        // Given
+        let p = Plane::default();
+        let r = Ray::new(Tuple::point(0.0, -1.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+
+        // When
+        let xs = p.intersects(&r);
+
+        // Then
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t(), 1.0);
+    }
+
+    #[test]
+    fn intersects_applies_translate_transform() {
+        // Given
+        let mut p = Plane::default();
+        p.set_transform(transformation::translation(0.0, -2.0, 0.0));
+        let r = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+        // When
+        let xs = p.intersects(&r);
+
+        // Then
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t(), 3.0);
+    }
+
+    #[test]
+    fn normal_on_rotated_plane() {
+        // Given
+        let mut p = Plane::default();
+        p.set_transform(transformation::rotation_z(PI / 2.0));
+
+        // When
+        let n = p.normal_at(&Tuple::point(0.0, 0.0, 0.0));
+
+        // Then
+        assert_eq!(n, Tuple::vector(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn set_material_sets_member() {
+        // Given
+        let mut p = Plane::default();
+        let m = Material::new(Color::new(0.0, 0.0, 0.0), 1.0, 0.0, 0.0, 0.0);
+
+        // When
+        p.set_material(m.clone());
+
+        // Then
+        assert_eq!(p.material(), &m);
+    }
+
+    #[test]
+    fn local_bounds_is_infinite_in_xz() {
+        // Given
+        let p = Plane::default();
+
+        // When
+        let b = p.local_bounds();
+
+        // Then
+        assert_eq!(b.min(), &Tuple::point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY));
+        assert_eq!(b.max(), &Tuple::point(f64::INFINITY, 0.0, f64::INFINITY));
+    }
+}
@@ -0,0 +1,173 @@
+use crate::raytracer::{ray::Ray, tuple::Tuple};
+
+use super::{bounds::BoundingBox, intersection::Intersection, materials::Material, object::Object, transform::Transforms, triangle::intersect_triangle};
+
+/// A triangle with a normal per vertex, which are interpolated across the surface to fake a curved shape.
+pub struct SmoothTriangle {
+    transforms: Transforms,
+    material: Material,
+    p1: Tuple,
+    p2: Tuple,
+    p3: Tuple,
+    n1: Tuple,
+    n2: Tuple,
+    n3: Tuple,
+    e1: Tuple,
+    e2: Tuple,
+}
+
+impl SmoothTriangle {
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple, n1: Tuple, n2: Tuple, n3: Tuple) -> SmoothTriangle {
+        let e1 = &p2 - &p1;
+        let e2 = &p3 - &p1;
+        Self {
+            transforms: Transforms::default(),
+            material: Material::default(),
+            p1, p2, p3, n1, n2, n3, e1, e2,
+        }
+    }
+
+    pub fn p1(&self) -> &Tuple {
+        &self.p1
+    }
+
+    pub fn p2(&self) -> &Tuple {
+        &self.p2
+    }
+
+    pub fn p3(&self) -> &Tuple {
+        &self.p3
+    }
+
+    pub fn n1(&self) -> &Tuple {
+        &self.n1
+    }
+
+    pub fn n2(&self) -> &Tuple {
+        &self.n2
+    }
+
+    pub fn n3(&self) -> &Tuple {
+        &self.n3
+    }
+}
+
+impl Object for SmoothTriangle {
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        match intersect_triangle(ray, &self.p1, &self.e1, &self.e2) {
+            Some((t, u, v)) => vec![Intersection::with_uv(t, self, u, v)],
+            None => vec![],
+        }
+    }
+
+    /// Without barycentric coordinates only the flat face normal is known.
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        self.e2.cross(&self.e1).normalize()
+    }
+
+    fn local_normal_at_hit(&self, point: &Tuple, hit: &Intersection) -> Tuple {
+        match (hit.u(), hit.v()) {
+            (Some(u), Some(v)) => &self.n2 * u + &(&self.n3 * v) + &(&self.n1 * (1.0 - u - v)),
+            _ => self.local_normal_at(point),
+        }
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        for p in [&self.p1, &self.p2, &self.p3] {
+            bounds.add_point(p);
+        }
+        bounds
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut Transforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use crate::raytracer::objects::computations::prepare_computations;
+
+    use super::*;
+
+    fn smooth_triangle() -> SmoothTriangle {
+        SmoothTriangle::new(
+            Tuple::point(0.0, 1.0, 0.0),
+            Tuple::point(-1.0, 0.0, 0.0),
+            Tuple::point(1.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+            Tuple::vector(-1.0, 0.0, 0.0),
+            Tuple::vector(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn new_sets_members() {
+        // When
+        let tri = smooth_triangle();
+
+        // Then
+        assert_eq!(tri.p1(), &Tuple::point(0.0, 1.0, 0.0));
+        assert_eq!(tri.p2(), &Tuple::point(-1.0, 0.0, 0.0));
+        assert_eq!(tri.p3(), &Tuple::point(1.0, 0.0, 0.0));
+        assert_eq!(tri.n1(), &Tuple::vector(0.0, 1.0, 0.0));
+        assert_eq!(tri.n2(), &Tuple::vector(-1.0, 0.0, 0.0));
+        assert_eq!(tri.n3(), &Tuple::vector(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn intersects_stores_u_and_v() {
+        // Given
+        let tri = smooth_triangle();
+        let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = tri.local_intersect(&r);
+
+        // Then
+        assert!(approx_eq!(f64, xs[0].u().unwrap(), 0.45, epsilon = 0.00001));
+        assert!(approx_eq!(f64, xs[0].v().unwrap(), 0.25, epsilon = 0.00001));
+    }
+
+    #[test]
+    fn normal_at_hit_interpolates_normal() {
+        // Given
+        let tri = smooth_triangle();
+        let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+
+        // When
+        let n = tri.normal_at_hit(&Tuple::point(0.0, 0.0, 0.0), &i);
+
+        // Then
+        assert_eq!(n, Tuple::vector(-0.5547, 0.83205, 0.0));
+    }
+
+    #[test]
+    fn prepare_computations_uses_interpolated_normal() {
+        // Given
+        let tri = smooth_triangle();
+        let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+        let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+
+        // Then
+        assert_eq!(comps.normalv(), &Tuple::vector(-0.5547, 0.83205, 0.0));
+    }
+}
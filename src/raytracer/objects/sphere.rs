@@ -1,16 +1,15 @@
-use crate::raytracer::{matrix::Matrix, ray::Ray, tuple::Tuple};
+use crate::raytracer::{ray::Ray, tuple::Tuple};
 
-use super::{intersection::Intersection, materials::Material, object::Object};
+use super::{bounds::BoundingBox, intersection::Intersection, materials::Material, object::Object, transform::Transforms};
 
+#[derive(Default)]
 pub struct Sphere { 
-    transform: Matrix,
+    transforms: Transforms,
     material: Material
 }
 
 impl Object for Sphere {
-    fn intersects(&self, ray: &Ray) -> Vec<Intersection> {
-        let ray = ray.transform(&self.transform.inverse());
-
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
         let sphere_to_ray = ray.origin() - &Tuple::point(0.0, 0.0, 0.0);
         let a = ray.direction().dot(ray.direction());
         let b = 2.0 * ray.direction().dot(&sphere_to_ray);
@@ -30,12 +29,16 @@ impl Object for Sphere {
         vec![t1_intersection, t2_intersection]
     }
 
-    fn transform(&self) -> &Matrix {
-        &self.transform
+    fn local_bounds(&self) -> BoundingBox {
+        BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
     }
 
-    fn set_transform(&mut self, transform: Matrix) {
-        self.transform = transform;
+    fn transforms_mut(&mut self) -> &mut Transforms {
+        &mut self.transforms
     }
 
     fn material(&self) -> &Material {
@@ -46,18 +49,8 @@ impl Object for Sphere {
         self.material = material;
     }
 
-    fn normal_at(&self, point: &Tuple) -> Tuple {
-        let object_point = &self.transform.inverse() * point;
-        let object_normal = &object_point - &Tuple::point(0.0, 0.0, 0.0);
-        let world_normal = &self.transform.inverse().transpose() * &object_normal;
-        let world_normal = Tuple::vector(world_normal.x(), world_normal.y(), world_normal.z());
-        world_normal.normalize()
-    }
-}
-
-impl Default for Sphere {
-    fn default() -> Sphere {
-        Self { transform: Matrix::identity(4, 4), material: Material::default() }
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        point - &Tuple::point(0.0, 0.0, 0.0)
     }
 }
 
@@ -65,7 +58,7 @@ impl Default for Sphere {
 mod tests {
     use std::f64::consts::PI;
 
-    use crate::raytracer::{color::Color, transformation};
+    use crate::raytracer::{color::Color, matrix::Matrix, transformation};
 
     use super::*;
 
@@ -249,6 +242,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn normal_on_translated_sphere() {
         // Given
         let mut s = Sphere::default();
@@ -302,4 +296,31 @@ mod tests {
         // Then
         assert_eq!(s.material(), &m);
     }
+
+    #[test]
+    fn local_bounds_is_unit_box() {
+        // Given
+        let s = Sphere::default();
+
+        // When
+        let b = s.local_bounds();
+
+        // Then
+        assert_eq!(b.min(), &Tuple::point(-1.0, -1.0, -1.0));
+        assert_eq!(b.max(), &Tuple::point(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn bounds_applies_transform() {
+        // Given
+        let mut s = Sphere::default();
+        s.set_transform(&transformation::translation(1.0, -3.0, 5.0) * &transformation::scaling(0.5, 2.0, 4.0));
+
+        // When
+        let b = s.bounds();
+
+        // Then
+        assert_eq!(b.min(), &Tuple::point(0.5, -5.0, 1.0));
+        assert_eq!(b.max(), &Tuple::point(1.5, -1.0, 9.0));
+    }
 }
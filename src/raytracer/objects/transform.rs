@@ -0,0 +1,103 @@
+use crate::raytracer::matrix::Matrix;
+
+/// A transformation matrix together with its inverse, which is computed once when the matrix is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transform {
+    matrix: Matrix,
+    inverse: Matrix,
+}
+
+impl Transform {
+    pub fn new(matrix: Matrix) -> Transform {
+        Self { inverse: matrix.inverse(), matrix }
+    }
+
+    /// Creates a transform whose inverse is already known, e.g. because it was composed from other inverses.
+    pub fn with_inverse(matrix: Matrix, inverse: Matrix) -> Transform {
+        Self { matrix, inverse }
+    }
+
+    pub fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    pub fn inverse(&self) -> &Matrix {
+        &self.inverse
+    }
+
+    /// The transform that applies `inner` first and then this one, composed from the cached inverses.
+    pub fn then(&self, inner: &Transform) -> Transform {
+        Self::with_inverse(&self.matrix * &inner.matrix, &inner.inverse * &self.inverse)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Self { matrix: Matrix::identity(4, 4), inverse: Matrix::identity(4, 4) }
+    }
+}
+
+/// The transform of an object and the combined transform of all groups containing it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transforms {
+    own: Transform,
+    parent: Transform,
+}
+
+impl Transforms {
+    pub fn own(&self) -> &Transform {
+        &self.own
+    }
+
+    pub fn set_own(&mut self, transform: Matrix) {
+        self.own = Transform::new(transform);
+    }
+
+    pub fn parent(&self) -> &Transform {
+        &self.parent
+    }
+
+    pub fn set_parent(&mut self, parent: Transform) {
+        self.parent = parent;
+    }
+
+    /// The transform from the object's space into world space, which is the parent transform of its children.
+    pub fn world(&self) -> Transform {
+        self.parent.then(&self.own)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::raytracer::transformation;
+
+    use super::*;
+
+    #[test]
+    fn new_caches_inverse() {
+        // When
+        let t = Transform::new(transformation::scaling(2.0, 4.0, 8.0));
+
+        // Then
+        assert_eq!(t.matrix(), &transformation::scaling(2.0, 4.0, 8.0));
+        assert_eq!(t.inverse(), &transformation::scaling(0.5, 0.25, 0.125));
+    }
+
+    #[test]
+    fn world_composes_parent_and_own_transform() {
+        // Given
+        let mut transforms = Transforms::default();
+        transforms.set_own(transformation::scaling(1.0, 2.0, 3.0));
+        transforms.set_parent(Transform::new(transformation::rotation_y(PI / 2.0)));
+
+        // When
+        let world = transforms.world();
+
+        // Then
+        let expected = transformation::rotation_y(PI / 2.0) * &transformation::scaling(1.0, 2.0, 3.0);
+        assert_eq!(world.matrix(), &expected);
+        assert_eq!(world.inverse(), &expected.inverse());
+    }
+}
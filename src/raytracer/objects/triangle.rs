@@ -0,0 +1,208 @@
+use crate::raytracer::{ray::Ray, tuple::Tuple, EPSILON};
+
+use super::{bounds::BoundingBox, intersection::Intersection, materials::Material, object::Object, transform::Transforms};
+
+/// A flat triangle with a single precomputed normal.
+pub struct Triangle {
+    transforms: Transforms,
+    material: Material,
+    p1: Tuple,
+    p2: Tuple,
+    p3: Tuple,
+    e1: Tuple,
+    e2: Tuple,
+    normal: Tuple,
+}
+
+impl Triangle {
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple) -> Triangle {
+        let e1 = &p2 - &p1;
+        let e2 = &p3 - &p1;
+        let normal = e2.cross(&e1).normalize();
+        Self {
+            transforms: Transforms::default(),
+            material: Material::default(),
+            p1, p2, p3, e1, e2, normal,
+        }
+    }
+
+    pub fn p1(&self) -> &Tuple {
+        &self.p1
+    }
+
+    pub fn p2(&self) -> &Tuple {
+        &self.p2
+    }
+
+    pub fn p3(&self) -> &Tuple {
+        &self.p3
+    }
+
+    pub fn e1(&self) -> &Tuple {
+        &self.e1
+    }
+
+    pub fn e2(&self) -> &Tuple {
+        &self.e2
+    }
+
+    pub fn normal(&self) -> &Tuple {
+        &self.normal
+    }
+}
+
+impl Object for Triangle {
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        match intersect_triangle(ray, &self.p1, &self.e1, &self.e2) {
+            Some((t, u, v)) => vec![Intersection::with_uv(t, self, u, v)],
+            None => vec![],
+        }
+    }
+
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        self.normal.clone()
+    }
+
+    fn local_bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        for p in [&self.p1, &self.p2, &self.p3] {
+            bounds.add_point(p);
+        }
+        bounds
+    }
+
+    fn transforms(&self) -> &Transforms {
+        &self.transforms
+    }
+
+    fn transforms_mut(&mut self) -> &mut Transforms {
+        &mut self.transforms
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+}
+
+/// Möller–Trumbore intersection, returns `t` along with the barycentric `u` and `v` of the hit.
+pub(crate) fn intersect_triangle(ray: &Ray, p1: &Tuple, e1: &Tuple, e2: &Tuple) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = ray.direction().cross(e2);
+    let det = e1.dot(&dir_cross_e2);
+
+    // the ray is parallel to the triangle
+    if det.abs() < EPSILON {
+        return None;
+    }
+
+    let f = 1.0 / det;
+    let p1_to_origin = ray.origin() - p1;
+    let u = f * p1_to_origin.dot(&dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = p1_to_origin.cross(e1);
+    let v = f * ray.direction().dot(&origin_cross_e1);
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = f * e2.dot(&origin_cross_e1);
+    Some((t, u, v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle() -> Triangle {
+        Triangle::new(Tuple::point(0.0, 1.0, 0.0), Tuple::point(-1.0, 0.0, 0.0), Tuple::point(1.0, 0.0, 0.0))
+    }
+
+    #[test]
+    fn new_precomputes_edges_and_normal() {
+        // When
+        let t = triangle();
+
+        // Then
+        assert_eq!(t.p1(), &Tuple::point(0.0, 1.0, 0.0));
+        assert_eq!(t.p2(), &Tuple::point(-1.0, 0.0, 0.0));
+        assert_eq!(t.p3(), &Tuple::point(1.0, 0.0, 0.0));
+        assert_eq!(t.e1(), &Tuple::vector(-1.0, -1.0, 0.0));
+        assert_eq!(t.e2(), &Tuple::vector(1.0, -1.0, 0.0));
+        assert_eq!(t.normal(), &Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn normal_is_constant() {
+        // Given
+        let t = triangle();
+
+        // When & Then
+        assert_eq!(t.local_normal_at(&Tuple::point(0.0, 0.5, 0.0)), *t.normal());
+        assert_eq!(t.local_normal_at(&Tuple::point(-0.5, 0.75, 0.0)), *t.normal());
+        assert_eq!(t.local_normal_at(&Tuple::point(0.5, 0.25, 0.0)), *t.normal());
+    }
+
+    #[test]
+    fn intersects_ray_parallel_to_triangle() {
+        // Given
+        let t = triangle();
+        let r = Ray::new(Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 1.0, 0.0));
+
+        // When & Then
+        assert!(t.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn intersects_ray_misses_edges() {
+        // Given
+        let t = triangle();
+        let rays = [
+            // misses the p1-p3 edge
+            Ray::new(Tuple::point(1.0, 1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0)),
+            // misses the p1-p2 edge
+            Ray::new(Tuple::point(-1.0, 1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0)),
+            // misses the p2-p3 edge
+            Ray::new(Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0)),
+        ];
+
+        for r in rays {
+            // When & Then
+            assert!(t.local_intersect(&r).is_empty());
+        }
+    }
+
+    #[test]
+    fn intersects_ray_strikes_triangle() {
+        // Given
+        let t = triangle();
+        let r = Ray::new(Tuple::point(0.0, 0.5, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = t.local_intersect(&r);
+
+        // Then
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t(), 2.0);
+        assert!(xs[0].u().is_some());
+        assert!(xs[0].v().is_some());
+    }
+
+    #[test]
+    fn local_bounds_contains_points() {
+        // Given
+        let t = Triangle::new(Tuple::point(-3.0, 7.0, 2.0), Tuple::point(6.0, 2.0, -4.0), Tuple::point(2.0, -1.0, -1.0));
+
+        // When
+        let b = t.local_bounds();
+
+        // Then
+        assert_eq!(b.min(), &Tuple::point(-3.0, -1.0, -4.0));
+        assert_eq!(b.max(), &Tuple::point(6.0, 7.0, 2.0));
+    }
+}
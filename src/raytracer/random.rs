@@ -0,0 +1,71 @@
+//! A small xorshift generator, good enough to jitter samples without pulling in a dependency.
+
+/// A pseudo-random number generator whose sequence only depends on the seeds it was created with,
+/// so renders come out the same no matter which thread takes a sample.
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    /// Creates a generator from `seeds`, e.g. the coordinates of a pixel and the index of a sample in it.
+    pub fn new(seeds: &[u64]) -> Random {
+        let state = seeds.iter().fold(0x853c_49e6_748f_ea9b, |state, seed| split_mix(state ^ seed));
+        Self { state: split_mix(state) }
+    }
+
+    /// Returns the next pseudo-random number in the range [0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        // the upper 53 bits fill the mantissa of a double
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// Scrambles consecutive seeds into well distributed, non-zero states.
+fn split_mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (z ^ (z >> 31)).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_f64_is_between_zero_and_one() {
+        // Given
+        let mut rng = Random::new(&[42]);
+
+        // When
+        let values: Vec<f64> = (0..10_000).map(|_| rng.next_f64()).collect();
+
+        // Then
+        assert!(values.iter().all(|v| (0.0..1.0).contains(v)));
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        assert!((mean - 0.5).abs() < 0.02, "mean {}", mean);
+    }
+
+    #[test]
+    fn new_with_same_seeds_repeats_sequence() {
+        // Given
+        let mut a = Random::new(&[3, 7, 1]);
+        let mut b = Random::new(&[3, 7, 1]);
+        let mut c = Random::new(&[7, 3, 1]);
+
+        // When
+        let xs: Vec<f64> = (0..5).map(|_| a.next_f64()).collect();
+        let ys: Vec<f64> = (0..5).map(|_| b.next_f64()).collect();
+        let zs: Vec<f64> = (0..5).map(|_| c.next_f64()).collect();
+
+        // Then
+        assert_eq!(xs, ys);
+        assert_ne!(xs, zs);
+    }
+}
@@ -0,0 +1,993 @@
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path};
+
+use crate::raytracer::{
+    camera::Camera,
+    color::Color,
+    matrix::Matrix,
+    obj_parser::parse_obj,
+    objects::{
+        cone::Cone,
+        csg::{Csg, CsgOperation},
+        cube::Cube,
+        cylinder::Cylinder,
+        group::Group,
+        lights::{AreaLight, Attenuation, DirectionalLight, Light, PointLight, SpotLight},
+        materials::Material,
+        object::Object,
+        patterns::{CheckersPattern, GradientPattern, Pattern, RingPattern, StripePattern},
+        plane::Plane,
+        sphere::Sphere,
+        triangle::Triangle,
+    },
+    transformation,
+    tuple::Tuple,
+    world::World,
+    EPSILON,
+};
+
+use super::{yaml::{parse_yaml, Node, Value}, SceneError};
+
+// Definitions may refer to other definitions, this stops the ones that end up referring to themselves.
+const MAX_DEFINE_DEPTH: usize = 32;
+
+/// The keys every shape accepts, in addition to its own.
+const SHAPE_KEYS: [&str; 3] = ["add", "material", "transform"];
+
+pub struct Scene {
+    camera: Camera,
+    world: World,
+}
+
+impl Scene {
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn into_parts(self) -> (Camera, World) {
+        (self.camera, self.world)
+    }
+}
+
+/// Loads a scene file, OBJ files referenced by it are looked up relative to the scene file.
+pub fn load_scene(path: &Path) -> Result<Scene, SceneError> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|err| SceneError::new(&format!("failed to read '{}': {}", path.display(), err)))?;
+    parse_scene(&source, path.parent().unwrap_or(Path::new("")))
+}
+
+/// Builds a scene from a list of `add` entries (for the camera, lights and shapes) and `define` entries,
+/// which give names to values so they can be reused and extended.
+pub fn parse_scene(source: &str, base_dir: &Path) -> Result<Scene, SceneError> {
+    let root = parse_yaml(source)?;
+    let items = match root.value() {
+        Value::Sequence(items) => items.as_slice(),
+        Value::Null => &[],
+        _ => return Err(SceneError::at(root.line(), "a scene has to be a list of 'add' and 'define' entries")),
+    };
+
+    let mut loader = Loader { base_dir, defines: HashMap::new(), camera: None, world: World::default() };
+    for item in items {
+        loader.load_item(item)?;
+    }
+
+    match loader.camera {
+        Some(camera) => Ok(Scene { camera, world: loader.world }),
+        None => Err(SceneError::new("the scene doesn't add a camera")),
+    }
+}
+
+struct Loader<'a> {
+    base_dir: &'a Path,
+    defines: HashMap<String, Node>,
+    camera: Option<Camera>,
+    world: World,
+}
+
+impl Loader<'_> {
+    fn load_item(&mut self, item: &Node) -> Result<(), SceneError> {
+        if let Some(name) = item.get("define") {
+            return self.define(item, name);
+        }
+
+        let kind = item.get("add")
+            .ok_or_else(|| SceneError::at(item.line(), "expected an 'add' or 'define' entry"))?;
+        match str_value(kind)? {
+            "camera" => self.camera = Some(camera(item)?),
+            "light" => self.world.add_light(light(item)?),
+            _ => {
+                let object = self.object(item, 0, None)?;
+                self.world.add_object(object);
+            }
+        }
+        Ok(())
+    }
+
+    fn define(&mut self, item: &Node, name: &Node) -> Result<(), SceneError> {
+        check_keys(item, &["define", "value", "extend"], "define")?;
+        let mut value = required(item, "value")?.clone();
+        if let Some(base) = item.get("extend") {
+            value = merge(self.lookup(base)?, &value)?;
+        }
+        self.defines.insert(str_value(name)?.to_string(), value);
+        Ok(())
+    }
+
+    fn lookup(&self, name: &Node) -> Result<&Node, SceneError> {
+        let key = str_value(name)?;
+        self.defines.get(key).ok_or_else(|| SceneError::at(name.line(), &format!("'{}' is not defined", key)))
+    }
+
+    // Children of groups and CSG shapes that set no material of their own get the `inherited` one.
+    // `depth` counts the definitions expanded on the way to the item, nesting shapes doesn't add to it.
+    fn object(&self, item: &Node, depth: usize, inherited: Option<&Material>) -> Result<Box<dyn Object>, SceneError> {
+        if depth > MAX_DEFINE_DEPTH {
+            return Err(SceneError::at(item.line(), "definitions are nested too deeply"));
+        }
+
+        let kind = required(item, "add")?;
+        let material = match item.get("material") {
+            Some(material) => Some(self.material(material)?),
+            None => inherited.cloned(),
+        };
+        let (mut object, keys): (Box<dyn Object>, &[&str]) = match str_value(kind)? {
+            "sphere" => (Box::new(Sphere::default()), &[]),
+            "plane" => (Box::new(Plane::default()), &[]),
+            "cube" => (Box::new(Cube::default()), &[]),
+            "cylinder" => {
+                let (minimum, maximum, closed) = limits(item)?;
+                (Box::new(Cylinder::new(minimum, maximum, closed)), &["min", "max", "closed"])
+            }
+            "cone" => {
+                let (minimum, maximum, closed) = limits(item)?;
+                (Box::new(Cone::new(minimum, maximum, closed)), &["min", "max", "closed"])
+            }
+            "triangle" => {
+                let [p1, p2, p3] = ["p1", "p2", "p3"].map(|key| required(item, key).and_then(point));
+                (Box::new(Triangle::new(p1?, p2?, p3?)), &["p1", "p2", "p3"])
+            }
+            "group" => {
+                let children = match item.get("children") {
+                    Some(children) => sequence(children)?.iter()
+                        .map(|child| self.object(child, depth, material.as_ref()))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    None => vec![],
+                };
+                (Box::new(Group::new(children)), &["children"])
+            }
+            "csg" => {
+                let operation = required(item, "operation")?;
+                let operation = match str_value(operation)? {
+                    "union" => CsgOperation::Union,
+                    "intersection" => CsgOperation::Intersection,
+                    "difference" => CsgOperation::Difference,
+                    other => return Err(SceneError::at(operation.line(), &format!("unknown CSG operation '{}'", other))),
+                };
+                let left = self.object(required(item, "left")?, depth, material.as_ref())?;
+                let right = self.object(required(item, "right")?, depth, material.as_ref())?;
+                (Box::new(Csg::new(operation, left, right)), &["operation", "left", "right"])
+            }
+            "obj" => (Box::new(self.obj_file(required(item, "file")?)?), &["file"]),
+            name => {
+                // a defined shape, the entry may override any of the definition's keys
+                let definition = self.lookup(kind)
+                    .map_err(|_| SceneError::at(kind.line(), &format!("unknown shape '{}'", name)))?;
+                let overrides = Node::new(
+                    Value::Mapping(mapping(item)?.iter().filter(|(k, _)| k != "add").cloned().collect()),
+                    item.line(),
+                );
+                return self.object(&merge(definition, &overrides)?, depth + 1, inherited);
+            }
+        };
+
+        let allowed: Vec<&str> = SHAPE_KEYS.iter().chain(keys).copied().collect();
+        check_keys(item, &allowed, str_value(kind)?)?;
+
+        // the children of groups and CSG shapes already got the material, setting it again would override their own
+        if let Some(material) = material.filter(|_| !matches!(str_value(kind), Ok("group" | "csg"))) {
+            object.set_material(material);
+        }
+        if let Some(transform) = item.get("transform") {
+            object.set_transform(self.invertible_transform(transform)?);
+        }
+        Ok(object)
+    }
+
+    fn obj_file(&self, file: &Node) -> Result<Group, SceneError> {
+        let path = self.base_dir.join(str_value(file)?);
+        let error = |err: &dyn std::fmt::Display| SceneError::at(file.line(), &format!("failed to read '{}': {}", path.display(), err));
+        let reader = File::open(&path).map_err(|err| error(&err))?;
+        let obj = parse_obj(BufReader::new(reader)).map_err(|err| error(&err))?;
+        Ok(obj.to_group())
+    }
+
+    fn material(&self, node: &Node) -> Result<Material, SceneError> {
+        let node = match node.as_str() {
+            Some(_) => self.lookup(node)?,
+            None => node,
+        };
+
+        let mut material = Material::default();
+        for (key, value) in mapping(node)? {
+            match key.as_str() {
+                "color" => material.set_color(color(value)?),
+                "ambient" => material.set_ambient(number(value)?),
+                "diffuse" => material.set_diffuse(number(value)?),
+                "specular" => material.set_specular(number(value)?),
+                "shininess" => material.set_shininess(number(value)?),
+                "reflective" => material.set_reflective(number(value)?),
+                "transparency" => material.set_transparency(number(value)?),
+                "refractive-index" => material.set_refractive_index(number(value)?),
+                "pattern" => material.set_pattern(self.pattern(value)?),
+                _ => return Err(unknown_key(key, value, "material")),
+            }
+        }
+        Ok(material)
+    }
+
+    fn pattern(&self, node: &Node) -> Result<Box<dyn Pattern>, SceneError> {
+        check_keys(node, &["type", "colors", "transform"], "pattern")?;
+        let colors = required(node, "colors")?;
+        let [a, b] = match sequence(colors)?.as_slice() {
+            [a, b] => [color(a)?, color(b)?],
+            _ => return Err(SceneError::at(colors.line(), "a pattern needs exactly two colors")),
+        };
+
+        let kind = required(node, "type")?;
+        let mut pattern: Box<dyn Pattern> = match str_value(kind)? {
+            "stripes" => Box::new(StripePattern::new(a, b)),
+            "gradient" => Box::new(GradientPattern::new(a, b)),
+            "rings" => Box::new(RingPattern::new(a, b)),
+            "checkers" => Box::new(CheckersPattern::new(a, b)),
+            other => return Err(SceneError::at(kind.line(), &format!("unknown pattern '{}'", other))),
+        };
+        if let Some(transform) = node.get("transform") {
+            pattern.set_transform(self.invertible_transform(transform)?);
+        }
+        Ok(pattern)
+    }
+
+    // Shapes and patterns work with the inverse of their transform, so one that flattens space is an error.
+    fn invertible_transform(&self, node: &Node) -> Result<Matrix, SceneError> {
+        let transform = self.transform(node, 0)?;
+        if !transform.is_invertible() {
+            return Err(SceneError::at(node.line(), "the transform can't be inverted, e.g. because it scales by zero"));
+        }
+        Ok(transform)
+    }
+
+    // The transformations of the list are applied in order, names refer to defined lists of transformations.
+    fn transform(&self, node: &Node, depth: usize) -> Result<Matrix, SceneError> {
+        if depth > MAX_DEFINE_DEPTH {
+            return Err(SceneError::at(node.line(), "definitions are nested too deeply"));
+        }
+
+        let mut result = Matrix::identity(4, 4);
+        for item in sequence(node)? {
+            let matrix = match item.as_str() {
+                Some(_) => self.transform(self.lookup(item)?, depth + 1)?,
+                None => transformation(item)?,
+            };
+            result = &matrix * &result;
+        }
+        Ok(result)
+    }
+}
+
+fn camera(item: &Node) -> Result<Camera, SceneError> {
+    check_keys(item, &["add", "width", "height", "field-of-view", "from", "to", "up"], "camera")?;
+    let mut camera = Camera::new(
+        usize_value(required(item, "width")?)?,
+        usize_value(required(item, "height")?)?,
+        number(required(item, "field-of-view")?)?,
+    );
+    let from = point(required(item, "from")?)?;
+    let to_node = required(item, "to")?;
+    let to = point(to_node)?;
+    let up_node = required(item, "up")?;
+    let up = vector(up_node)?;
+    if from == to {
+        return Err(SceneError::at(to_node.line(), "the camera can't look 'to' the point it looks 'from'"));
+    }
+    // the view transform is built from the direction perpendicular to both
+    if (&to - &from).normalize().cross(&up).magnitude() < EPSILON {
+        return Err(SceneError::at(up_node.line(), "the camera's 'up' can't be zero or point along its view direction"));
+    }
+    camera.set_transform(transformation::view_transform(&from, &to, &up));
+    Ok(camera)
+}
+
+// The kind of light is told apart by its keys: area lights have a `corner`, spot lights are
+// at a point and have a `direction`, directional lights only have a `direction`.
+fn light(item: &Node) -> Result<Box<dyn Light>, SceneError> {
+    let intensity = || color(required(item, "intensity")?);
+    match (item.get("corner"), item.get("at"), item.get("direction")) {
+        (Some(_), _, _) => area_light(item),
+        (None, Some(at), None) => {
+            check_keys(item, &["add", "at", "intensity", "attenuation"], "light")?;
+            let mut light = PointLight::new(point(at)?, intensity()?);
+            light.set_attenuation(item.get("attenuation").map_or(Ok(Attenuation::None), attenuation)?);
+            Ok(Box::new(light))
+        }
+        (None, Some(at), Some(direction)) => {
+            check_keys(item, &["add", "at", "direction", "inner-angle", "outer-angle", "intensity", "attenuation"], "spot light")?;
+            let inner_angle = number(required(item, "inner-angle")?)?;
+            let outer_angle = item.get("outer-angle").map_or(Ok(inner_angle), number)?;
+            let mut light = SpotLight::new(point(at)?, light_direction(direction)?, inner_angle, outer_angle, intensity()?);
+            light.set_attenuation(item.get("attenuation").map_or(Ok(Attenuation::None), attenuation)?);
+            Ok(Box::new(light))
+        }
+        (None, None, Some(direction)) => {
+            check_keys(item, &["add", "direction", "intensity"], "directional light")?;
+            Ok(Box::new(DirectionalLight::new(light_direction(direction)?, intensity()?)))
+        }
+        (None, None, None) => Err(SceneError::at(item.line(), "a light needs an 'at', 'direction' or 'corner' key")),
+    }
+}
+
+// Lights normalize their direction, which a zero vector doesn't have.
+fn light_direction(node: &Node) -> Result<Tuple, SceneError> {
+    let direction = vector(node)?;
+    if direction.magnitude() == 0.0 {
+        return Err(SceneError::at(node.line(), "a light's 'direction' can't be zero"));
+    }
+    Ok(direction)
+}
+
+fn area_light(item: &Node) -> Result<Box<dyn Light>, SceneError> {
+    check_keys(item, &["add", "corner", "uvec", "usteps", "vvec", "vsteps", "jitter", "intensity"], "area light")?;
+    let mut light = AreaLight::new(
+        point(required(item, "corner")?)?,
+        vector(required(item, "uvec")?)?,
+        usize_value(required(item, "usteps")?)?,
+        vector(required(item, "vvec")?)?,
+        usize_value(required(item, "vsteps")?)?,
+        color(required(item, "intensity")?)?,
+    );
+    light.set_jitter(item.get("jitter").map_or(Ok(false), bool_value)?);
+    Ok(Box::new(light))
+}
+
+// Either the name of a falloff or a mapping of the `constant`, `linear` and `quadratic` coefficients.
+// Like the named falloffs, explicit coefficients never make a light brighter than its intensity,
+// since the factor they give is capped at 1.
+fn attenuation(node: &Node) -> Result<Attenuation, SceneError> {
+    if node.as_mapping().is_some() {
+        check_keys(node, &["constant", "linear", "quadratic"], "attenuation")?;
+        let constant = node.get("constant").map_or(Ok(1.0), number)?;
+        let linear = node.get("linear").map_or(Ok(0.0), number)?;
+        let quadratic = node.get("quadratic").map_or(Ok(0.0), number)?;
+        if constant < 0.0 || linear < 0.0 || quadratic < 0.0 {
+            return Err(SceneError::at(node.line(), "attenuation coefficients can't be negative"));
+        }
+        return Ok(Attenuation::Coefficients { constant, linear, quadratic });
+    }
+
+    match str_value(node)? {
+        "none" => Ok(Attenuation::None),
+        "linear" => Ok(Attenuation::Linear),
+        "inverse-square" => Ok(Attenuation::InverseSquare),
+        other => Err(SceneError::at(node.line(), &format!("unknown attenuation '{}', expected none, linear, inverse-square or coefficients", other))),
+    }
+}
+
+// Reads the optional `min`, `max` and `closed` keys of cylinders and cones.
+fn limits(item: &Node) -> Result<(f64, f64, bool), SceneError> {
+    let minimum = item.get("min").map_or(Ok(f64::NEG_INFINITY), number)?;
+    let maximum = item.get("max").map_or(Ok(f64::INFINITY), number)?;
+    let closed = item.get("closed").map_or(Ok(false), bool_value)?;
+    Ok((minimum, maximum, closed))
+}
+
+// Parses a single transformation like `[ translate, 1, 2, 3 ]`.
+fn transformation(node: &Node) -> Result<Matrix, SceneError> {
+    let items = sequence(node)?;
+    let name = items.first().ok_or_else(|| SceneError::at(node.line(), "expected a transformation"))?;
+    let args = items[1..].iter().map(number).collect::<Result<Vec<f64>, _>>()?;
+
+    match (str_value(name)?, args.as_slice()) {
+        ("translate", &[x, y, z]) => Ok(transformation::translation(x, y, z)),
+        ("scale", &[x, y, z]) => Ok(transformation::scaling(x, y, z)),
+        ("rotate-x", &[radians]) => Ok(transformation::rotation_x(radians)),
+        ("rotate-y", &[radians]) => Ok(transformation::rotation_y(radians)),
+        ("rotate-z", &[radians]) => Ok(transformation::rotation_z(radians)),
+        ("shear", &[xy, xz, yx, yz, zx, zy]) => Ok(transformation::shearing(xy, xz, yx, yz, zx, zy)),
+        ("translate" | "scale" | "rotate-x" | "rotate-y" | "rotate-z" | "shear", _) => {
+            Err(SceneError::at(node.line(), &format!("wrong number of arguments for '{}'", str_value(name)?)))
+        }
+        (other, _) => Err(SceneError::at(name.line(), &format!("unknown transformation '{}'", other))),
+    }
+}
+
+// Combines two mappings, the entries of `overrides` replace the ones of `base` with the same key.
+fn merge(base: &Node, overrides: &Node) -> Result<Node, SceneError> {
+    let overrides_entries = mapping(overrides)?;
+    let mut entries: Vec<(String, Node)> = mapping(base)?.iter()
+        .filter(|(key, _)| !overrides_entries.iter().any(|(k, _)| k == key))
+        .cloned()
+        .collect();
+    entries.extend(overrides_entries.iter().cloned());
+    Ok(Node::new(Value::Mapping(entries), overrides.line()))
+}
+
+fn check_keys(node: &Node, allowed: &[&str], what: &str) -> Result<(), SceneError> {
+    match mapping(node)?.iter().find(|(key, _)| !allowed.contains(&key.as_str())) {
+        Some((key, value)) => Err(unknown_key(key, value, what)),
+        None => Ok(()),
+    }
+}
+
+fn unknown_key(key: &str, value: &Node, what: &str) -> SceneError {
+    SceneError::at(value.line(), &format!("unknown key '{}' for {}", key, what))
+}
+
+fn required<'a>(node: &'a Node, key: &str) -> Result<&'a Node, SceneError> {
+    node.get(key).ok_or_else(|| SceneError::at(node.line(), &format!("missing '{}'", key)))
+}
+
+fn mapping(node: &Node) -> Result<&Vec<(String, Node)>, SceneError> {
+    node.as_mapping().ok_or_else(|| SceneError::at(node.line(), "expected a mapping"))
+}
+
+fn sequence(node: &Node) -> Result<&Vec<Node>, SceneError> {
+    node.as_sequence().ok_or_else(|| SceneError::at(node.line(), "expected a list"))
+}
+
+fn str_value(node: &Node) -> Result<&str, SceneError> {
+    node.as_str().ok_or_else(|| SceneError::at(node.line(), "expected a name"))
+}
+
+fn number(node: &Node) -> Result<f64, SceneError> {
+    node.as_str()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| SceneError::at(node.line(), "expected a number"))
+}
+
+fn usize_value(node: &Node) -> Result<usize, SceneError> {
+    node.as_str()
+        .and_then(|s| s.parse().ok())
+        .filter(|n| *n > 0)
+        .ok_or_else(|| SceneError::at(node.line(), "expected a positive whole number"))
+}
+
+fn bool_value(node: &Node) -> Result<bool, SceneError> {
+    match node.as_str() {
+        Some("true") => Ok(true),
+        Some("false") => Ok(false),
+        _ => Err(SceneError::at(node.line(), "expected 'true' or 'false'")),
+    }
+}
+
+fn triple(node: &Node) -> Result<[f64; 3], SceneError> {
+    match sequence(node)?.as_slice() {
+        [x, y, z] => Ok([number(x)?, number(y)?, number(z)?]),
+        _ => Err(SceneError::at(node.line(), "expected a list of three numbers")),
+    }
+}
+
+fn point(node: &Node) -> Result<Tuple, SceneError> {
+    let [x, y, z] = triple(node)?;
+    Ok(Tuple::point(x, y, z))
+}
+
+fn vector(node: &Node) -> Result<Tuple, SceneError> {
+    let [x, y, z] = triple(node)?;
+    Ok(Tuple::vector(x, y, z))
+}
+
+fn color(node: &Node) -> Result<Color, SceneError> {
+    let [red, green, blue] = triple(node)?;
+    Ok(Color::new(red, green, blue))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::raytracer::{objects::lights::LightSample, random::Random, ray::Ray};
+
+    use super::*;
+
+    const CAMERA: &str = "\
+- add: camera
+  width: 100
+  height: 50
+  field-of-view: 0.785
+  from: [ 0, 1.5, -5 ]
+  to: [ 0, 1, 0 ]
+  up: [ 0, 1, 0 ]
+";
+
+    fn parse(source: &str) -> Result<Scene, SceneError> {
+        parse_scene(&format!("{}{}", CAMERA, source), Path::new(""))
+    }
+
+    #[test]
+    fn parse_scene_reads_camera_and_light() {
+        // Given
+        let source = "\
+- add: light
+  at: [ -10, 10, -10 ]
+  intensity: [ 1, 0.5, 1 ]
+";
+
+        // When
+        let scene = parse(source).unwrap();
+
+        // Then
+        assert_eq!(scene.camera().hsize(), 100);
+        assert_eq!(scene.camera().vsize(), 50);
+        assert_eq!(scene.camera().field_of_view(), 0.785);
+        assert_eq!(scene.camera().transform(), &transformation::view_transform(
+            &Tuple::point(0.0, 1.5, -5.0),
+            &Tuple::point(0.0, 1.0, 0.0),
+            &Tuple::vector(0.0, 1.0, 0.0),
+        ));
+        assert_eq!(scene.world().lights().len(), 1);
+        assert_eq!(scene.world().lights()[0].samples(&Tuple::point(-10.0, 0.0, -10.0), &mut Random::new(&[])), vec![LightSample::new(Tuple::vector(0.0, 1.0, 0.0), 10.0)]);
+        assert_eq!(scene.world().lights()[0].intensity(), &Color::new(1.0, 0.5, 1.0));
+    }
+
+    #[test]
+    fn parse_scene_reads_mixed_lights() {
+        // Given
+        let source = "
+- add: light
+  at: [ 0, 10, 0 ]
+  intensity: [ 1, 1, 1 ]
+- add: light
+  direction: [ 0, -1, 0 ]
+  intensity: [ 0.5, 0.5, 0.5 ]
+- add: light
+  at: [ 0, 10, 0 ]
+  direction: [ 0, -1, 0 ]
+  inner-angle: 0.1
+  outer-angle: 0.2
+  intensity: [ 1, 0, 0 ]
+";
+
+        // When
+        let scene = parse(source).unwrap();
+
+        // Then
+        let lights = scene.world().lights();
+        let point_below = Tuple::point(0.0, 0.0, 0.0);
+        let point_aside = Tuple::point(5.0, 0.0, 0.0);
+        assert_eq!(lights.len(), 3);
+        assert_eq!(lights[1].intensity(), &Color::new(0.5, 0.5, 0.5));
+        assert_eq!(lights[1].samples(&point_aside, &mut Random::new(&[])), vec![LightSample::new(Tuple::vector(0.0, 1.0, 0.0), f64::INFINITY)]);
+        assert_eq!(lights[2].intensity_at(&point_below, &lights[2].samples(&point_below, &mut Random::new(&[])), scene.world()), 1.0);
+        assert_eq!(lights[2].intensity_at(&point_aside, &lights[2].samples(&point_aside, &mut Random::new(&[])), scene.world()), 0.0);
+    }
+
+    #[test]
+    fn parse_scene_reads_light_attenuation() {
+        // Given
+        let source = "
+- add: light
+  at: [ 0, 10, 0 ]
+  intensity: [ 1, 1, 1 ]
+  attenuation: inverse-square
+- add: light
+  at: [ 0, 10, 0 ]
+  direction: [ 0, -1, 0 ]
+  inner-angle: 0.1
+  intensity: [ 1, 1, 1 ]
+  attenuation: { linear: 0.5, quadratic: 0.25 }
+";
+
+        // When
+        let scene = parse(source).unwrap();
+
+        // Then
+        let lights = scene.world().lights();
+        assert_eq!(lights[0].attenuation(), Attenuation::InverseSquare);
+        assert_eq!(lights[1].attenuation(), Attenuation::Coefficients { constant: 1.0, linear: 0.5, quadratic: 0.25 });
+    }
+
+    #[test]
+    fn parse_scene_rejects_zero_camera_size() {
+        // Given
+        let source = CAMERA.replace("width: 100", "width: 0");
+
+        // When
+        let err = parse_scene(&source, Path::new("")).err().unwrap();
+
+        // Then
+        assert_eq!(err.line(), Some(2));
+        assert_eq!(err.message(), "expected a positive whole number");
+    }
+
+    #[test]
+    fn parse_scene_rejects_degenerate_camera_orientation() {
+        // Given
+        let cases = [
+            ("to: [ 0, 1, 0 ]", "to: [ 0, 1.5, -5 ]", 6, "the camera can't look 'to' the point it looks 'from'"),
+            ("up: [ 0, 1, 0 ]", "up: [ 0, -0.5, 5 ]", 7, "the camera's 'up' can't be zero or point along its view direction"),
+            ("up: [ 0, 1, 0 ]", "up: [ 0, 0, 0 ]", 7, "the camera's 'up' can't be zero or point along its view direction"),
+        ];
+
+        for (from, to, line, message) in cases {
+            let source = CAMERA.replace(from, to);
+
+            // When
+            let err = parse_scene(&source, Path::new("")).err().unwrap();
+
+            // Then
+            assert_eq!(err.line(), Some(line), "{}", to);
+            assert_eq!(err.message(), message);
+        }
+    }
+
+    #[test]
+    fn parse_scene_rejects_transforms_that_cannot_be_inverted() {
+        // Given
+        let cases = [
+            "
+- add: sphere
+  transform:
+    - [ scale, 0, 1, 1 ]
+",
+            "
+- add: sphere
+  material:
+    pattern:
+      type: stripes
+      colors:
+        - [ 1, 1, 1 ]
+        - [ 0, 0, 0 ]
+      transform:
+        - [ scale, 1, 0, 1 ]
+",
+        ];
+
+        for source in cases {
+            // When
+            let err = parse(source).err().unwrap();
+
+            // Then
+            assert_eq!(err.message(), "the transform can't be inverted, e.g. because it scales by zero", "{}", source);
+        }
+    }
+
+    #[test]
+    fn parse_scene_rejects_zero_light_direction() {
+        // Given
+        let cases = [
+            "
+- add: light
+  direction: [ 0, 0, 0 ]
+  intensity: [ 1, 1, 1 ]
+",
+            "
+- add: light
+  at: [ 0, 10, 0 ]
+  direction: [ 0, 0, 0 ]
+  intensity: [ 1, 1, 1 ]
+  inner-angle: 0.5
+  outer-angle: 0.7
+",
+        ];
+
+        for source in cases {
+            // When
+            let err = parse(source).err().unwrap();
+
+            // Then
+            assert_eq!(err.message(), "a light's 'direction' can't be zero", "{}", source);
+        }
+    }
+
+    #[test]
+    fn parse_scene_rejects_unknown_attenuation() {
+        // Given
+        let source = "
+- add: light
+  at: [ 0, 10, 0 ]
+  intensity: [ 1, 1, 1 ]
+  attenuation: cubic
+";
+
+        // When
+        let err = parse(source).err().unwrap();
+
+        // Then
+        assert_eq!(err.line(), Some(12));
+    }
+
+    #[test]
+    fn parse_scene_reads_attenuation_without_constant() {
+        // Given
+        let source = "
+- add: light
+  at: [ 0, 10, 0 ]
+  intensity: [ 1, 1, 1 ]
+  attenuation: { constant: 0, quadratic: 1 }
+";
+
+        // When
+        let scene = parse(source).unwrap();
+
+        // Then
+        let light = &scene.world().lights()[0];
+        assert_eq!(light.attenuation(), Attenuation::Coefficients { constant: 0.0, linear: 0.0, quadratic: 1.0 });
+        assert_eq!(light.attenuation().factor(0.5), 1.0);
+        assert_eq!(light.attenuation().factor(2.0), 0.25);
+    }
+
+    #[test]
+    fn parse_scene_rejects_negative_attenuation_coefficients() {
+        // Given
+        let source = "
+- add: light
+  at: [ 0, 10, 0 ]
+  intensity: [ 1, 1, 1 ]
+  attenuation: { constant: 1, linear: -0.5 }
+";
+
+        // When
+        let err = parse(source).err().unwrap();
+
+        // Then
+        assert_eq!(err.line(), Some(12));
+        assert_eq!(err.message(), "attenuation coefficients can't be negative");
+    }
+
+    #[test]
+    fn parse_scene_rejects_light_without_position_or_direction() {
+        // Given
+        let source = "
+- add: light
+  intensity: [ 1, 1, 1 ]
+";
+
+        // When
+        let err = parse(source).err().unwrap();
+
+        // Then
+        assert_eq!(err.message(), "a light needs an 'at', 'direction' or 'corner' key");
+    }
+
+    #[test]
+    fn parse_scene_reads_area_light() {
+        // Given
+        let source = "
+- add: light
+  corner: [ 0, 0, 0 ]
+  uvec: [ 2, 0, 0 ]
+  usteps: 2
+  vvec: [ 0, 0, 1 ]
+  vsteps: 1
+  jitter: false
+  intensity: [ 1, 1, 1 ]
+";
+
+        // When
+        let scene = parse(source).unwrap();
+
+        // Then
+        assert_eq!(scene.world().lights().len(), 1);
+        let origin = Tuple::point(0.0, 0.0, 0.0);
+        assert_eq!(scene.world().lights()[0].samples(&origin, &mut Random::new(&[])), vec![
+            LightSample::towards(&origin, &Tuple::point(0.5, 0.0, 0.5)),
+            LightSample::towards(&origin, &Tuple::point(1.5, 0.0, 0.5)),
+        ]);
+    }
+
+    #[test]
+    fn parse_scene_applies_material_and_transform() {
+        // Given
+        let source = "\
+- add: sphere
+  material:
+    color: [ 1, 0.2, 1 ]
+    diffuse: 0.7
+    reflective: 0.3
+    pattern:
+      type: stripes
+      colors: [ [ 1, 1, 1 ], [ 0, 0, 0 ] ]
+  transform:
+    - [ scale, 2, 2, 2 ]
+    - [ translate, 0, 1, 0 ]
+";
+
+        // When
+        let scene = parse(source).unwrap();
+
+        // Then
+        let sphere = &scene.world().objects()[0];
+        assert_eq!(sphere.material().color(), &Color::new(1.0, 0.2, 1.0));
+        assert_eq!(sphere.material().diffuse(), 0.7);
+        assert_eq!(sphere.material().reflective(), 0.3);
+        assert!(sphere.material().pattern().is_some());
+        assert_eq!(sphere.transform(), &(&transformation::translation(0.0, 1.0, 0.0) * &transformation::scaling(2.0, 2.0, 2.0)));
+    }
+
+    #[test]
+    fn parse_scene_resolves_defines() {
+        // Given
+        let source = "\
+- define: white-material
+  value:
+    color: [ 1, 1, 1 ]
+    ambient: 0.3
+- define: blue-material
+  extend: white-material
+  value:
+    color: [ 0, 0, 1 ]
+- define: standard-transform
+  value:
+    - [ translate, 1, -1, 1 ]
+    - [ scale, 0.5, 0.5, 0.5 ]
+- define: large-object
+  value:
+    - standard-transform
+    - [ scale, 3.5, 3.5, 3.5 ]
+- add: cube
+  material: blue-material
+  transform:
+    - large-object
+    - [ rotate-y, 3.141592653589793 ]
+";
+
+        // When
+        let scene = parse(source).unwrap();
+
+        // Then
+        let cube = &scene.world().objects()[0];
+        assert_eq!(cube.material().color(), &Color::new(0.0, 0.0, 1.0));
+        assert_eq!(cube.material().ambient(), 0.3);
+        let expected = &(&(&transformation::rotation_y(PI) * &transformation::scaling(3.5, 3.5, 3.5))
+            * &transformation::scaling(0.5, 0.5, 0.5))
+            * &transformation::translation(1.0, -1.0, 1.0);
+        assert_eq!(cube.transform(), &expected);
+    }
+
+    #[test]
+    fn parse_scene_builds_composite_shapes() {
+        // Given
+        let source = "\
+- define: unit-sphere
+  value:
+    add: sphere
+    transform: [ [ translate, 0, 0, 10 ] ]
+- add: group
+  transform: [ [ translate, 0, 0, -10 ] ]
+  children:
+    - add: unit-sphere
+    - add: csg
+      operation: difference
+      left: { add: cube }
+      right: { add: sphere, transform: [ [ scale, 2, 2, 2 ] ] }
+    - add: cylinder
+      min: -1
+      max: 1
+      closed: true
+      transform: [ [ translate, 5, 0, 0 ] ]
+";
+
+        // When
+        let scene = parse(source).unwrap();
+
+        // Then
+        let r = Ray::new(Tuple::point(0.0, 0.0, -20.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = scene.world().intersect_world(&r);
+        let ts: Vec<f64> = xs.iter().map(|i| i.t()).collect();
+        // the large sphere cuts the whole cube away and the cylinder is off to the side, leaving only the small sphere
+        assert_eq!(ts, vec![19.0, 21.0]);
+    }
+
+    #[test]
+    fn parse_scene_reads_groups_nested_deeper_than_definitions() {
+        // Given
+        let depth = MAX_DEFINE_DEPTH + 8;
+        let nested = (0..depth).fold("{ add: sphere }".to_string(), |child, _| format!("{{ add: group, children: [ {} ] }}", child));
+        let source = format!("- {}\n", nested);
+
+        // When
+        let scene = parse(&source).unwrap();
+
+        // Then
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(scene.world().intersect_world(&r).len(), 2);
+    }
+
+    #[test]
+    fn parse_scene_rejects_definition_containing_itself() {
+        // Given
+        let source = "\
+- define: tower
+  value:
+    add: group
+    children:
+      - add: sphere
+      - add: tower
+- add: tower
+";
+
+        // When
+        let err = parse(source).err().unwrap();
+
+        // Then
+        assert_eq!(err.message(), "definitions are nested too deeply");
+    }
+
+    #[test]
+    fn parse_scene_keeps_child_materials_over_group_material() {
+        // Given
+        let source = "\
+- add: group
+  material: { color: [ 1, 0, 0 ] }
+  children:
+    - add: sphere
+      transform: [ [ translate, -3, 0, 0 ] ]
+    - add: sphere
+      material: { color: [ 0, 0, 1 ] }
+      transform: [ [ translate, 3, 0, 0 ] ]
+";
+
+        // When
+        let scene = parse(source).unwrap();
+
+        // Then
+        let color_at = |x: f64| {
+            let r = Ray::new(Tuple::point(x, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+            scene.world().intersect_world(&r)[0].object().material().color().clone()
+        };
+        assert_eq!(color_at(-3.0), Color::new(1.0, 0.0, 0.0));
+        assert_eq!(color_at(3.0), Color::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn parse_scene_loads_obj_files() {
+        // Given
+        let dir = std::env::temp_dir().join(format!("scene-loader-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("triangle.obj"), "v 0 1 0\nv -1 0 0\nv 1 0 0\nf 1 2 3\n").unwrap();
+        let source = format!("{}- add: obj\n  file: triangle.obj\n", CAMERA);
+
+        // When
+        let result = parse_scene(&source, &dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Then
+        let scene = result.unwrap();
+        let r = Ray::new(Tuple::point(0.0, 0.5, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(scene.world().intersect_world(&r).len(), 1);
+    }
+
+    #[test]
+    fn parse_scene_reports_line_of_error() {
+        // Given
+        let cases = [
+            ("- add: sphere\n  colour: [ 1, 1, 1 ]\n", 9),
+            ("- add: sphere\n  material: missing\n", 9),
+            ("- add: teapot\n", 8),
+            ("- add: light\n  at: [ 1, 2 ]\n  intensity: [ 1, 1, 1 ]\n", 9),
+            ("- add: cone\n  min: low\n", 9),
+            ("- add: cube\n  transform:\n    - [ translate, 1, 2 ]\n", 10),
+            ("- add: plane\n  material:\n    pattern:\n      type: dots\n      colors: [ [ 1, 1, 1 ], [ 0, 0, 0 ] ]\n", 11),
+            ("- define: loop\n  value: [ loop ]\n- add: cube\n  transform: [ loop ]\n", 9),
+        ];
+
+        for (source, line) in cases {
+            // When
+            let result = parse(source);
+
+            // Then
+            assert_eq!(result.err().and_then(|e| e.line()), Some(line), "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn parse_scene_requires_camera() {
+        // When
+        let result = parse_scene("- add: sphere\n", Path::new(""));
+
+        // Then
+        assert_eq!(result.err(), Some(SceneError::new("the scene doesn't add a camera")));
+    }
+}
@@ -0,0 +1,40 @@
+use std::{error::Error, fmt::Display};
+
+pub mod yaml;
+pub mod loader;
+
+/// An error in a scene file, pointing at the line it was found on where possible.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneError {
+    line: Option<usize>,
+    message: String,
+}
+
+impl SceneError {
+    pub fn new(message: &str) -> SceneError {
+        Self { line: None, message: message.to_string() }
+    }
+
+    pub fn at(line: usize, message: &str) -> SceneError {
+        Self { line: Some(line), message: message.to_string() }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl Error for SceneError {}
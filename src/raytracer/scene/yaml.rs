@@ -0,0 +1,443 @@
+use super::SceneError;
+
+/// The subset of YAML used by scene files: block mappings and sequences, single line flow
+/// sequences (`[ 1, 2, 3 ]`) and mappings (`{ a: 1 }`), plain and quoted scalars and comments.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Scalar(String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(String, Node)>),
+}
+
+/// A value together with the line it starts on, so errors can point at the offending part of a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    value: Value,
+    line: usize,
+}
+
+impl Node {
+    pub fn new(value: Value, line: usize) -> Node {
+        Self { value, line }
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            Value::Scalar(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_sequence(&self) -> Option<&Vec<Node>> {
+        match &self.value {
+            Value::Sequence(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_mapping(&self) -> Option<&Vec<(String, Node)>> {
+        match &self.value {
+            Value::Mapping(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// Looks up `key` if the node is a mapping.
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        self.as_mapping()?.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+}
+
+pub fn parse_yaml(source: &str) -> Result<Node, SceneError> {
+    let mut lines = vec![];
+    for (index, raw) in source.lines().enumerate() {
+        let number = index + 1;
+        let text = strip_comment(raw).trim_end();
+        let content = text.trim_start();
+        if content.is_empty() || content == "---" {
+            continue;
+        }
+        if text[..text.len() - content.len()].contains('\t') {
+            return Err(SceneError::at(number, "tabs can't be used for indentation"));
+        }
+        lines.push(Line { number, indent: text.len() - content.len(), text: content });
+    }
+
+    if lines.is_empty() {
+        return Ok(Node::new(Value::Null, 1));
+    }
+
+    let mut parser = Parser { lines, pos: 0 };
+    let node = parser.parse_block(parser.lines[0].indent)?;
+    match parser.lines.get(parser.pos) {
+        Some(line) => Err(SceneError::at(line.number, "unexpected indentation")),
+        None => Ok(node),
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    number: usize,
+    indent: usize,
+    text: &'a str,
+}
+
+struct Parser<'a> {
+    lines: Vec<Line<'a>>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn current(&self) -> Option<Line<'a>> {
+        self.lines.get(self.pos).copied()
+    }
+
+    fn parse_block(&mut self, indent: usize) -> Result<Node, SceneError> {
+        match self.current() {
+            Some(line) if is_sequence_item(line.text) => self.parse_sequence(indent),
+            _ => self.parse_mapping(indent),
+        }
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> Result<Node, SceneError> {
+        let start = self.lines[self.pos].number;
+        let mut items = vec![];
+
+        while let Some(line) = self.current().filter(|l| l.indent == indent && is_sequence_item(l.text)) {
+            let rest = line.text[1..].trim_start();
+            if rest.is_empty() {
+                self.pos += 1;
+                items.push(self.parse_nested(indent, line.number)?);
+            } else if is_sequence_item(rest) || split_key(rest, line.number)?.is_some() {
+                // the item is a block of its own which starts right after the dash,
+                // so the line is treated as if it only contained the rest, indented accordingly
+                let item_indent = indent + line.text.len() - rest.len();
+                self.lines[self.pos] = Line { number: line.number, indent: item_indent, text: rest };
+                items.push(self.parse_block(item_indent)?);
+            } else {
+                self.pos += 1;
+                items.push(parse_flow(rest, line.number)?);
+            }
+        }
+
+        Ok(Node::new(Value::Sequence(items), start))
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> Result<Node, SceneError> {
+        let start = self.lines[self.pos].number;
+        let mut entries: Vec<(String, Node)> = vec![];
+
+        while let Some(line) = self.current().filter(|l| l.indent == indent && !is_sequence_item(l.text)) {
+            let (key, rest) = split_key(line.text, line.number)?
+                .ok_or_else(|| SceneError::at(line.number, &format!("expected 'key: value', found '{}'", line.text)))?;
+            if entries.iter().any(|(k, _)| *k == key) {
+                return Err(SceneError::at(line.number, &format!("duplicate key '{}'", key)));
+            }
+            self.pos += 1;
+
+            let value = if !rest.is_empty() {
+                parse_flow(rest, line.number)?
+            } else {
+                match self.current() {
+                    // a sequence may be indented at the same level as the key it belongs to
+                    Some(next) if next.indent == indent && is_sequence_item(next.text) => self.parse_sequence(indent)?,
+                    _ => self.parse_nested(indent, line.number)?,
+                }
+            };
+            entries.push((key, value));
+        }
+
+        Ok(Node::new(Value::Mapping(entries), start))
+    }
+
+    // Parses the block indented deeper than its parent, or an empty value if there is none.
+    fn parse_nested(&mut self, parent_indent: usize, line: usize) -> Result<Node, SceneError> {
+        match self.current() {
+            Some(next) if next.indent > parent_indent => self.parse_block(next.indent),
+            _ => Ok(Node::new(Value::Null, line)),
+        }
+    }
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+// Removes a comment, which starts with a `#` at the beginning of the line or after whitespace, outside of quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => return &line[..i],
+            None => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+// Splits `key: value` at the first colon outside of quotes that is followed by whitespace or the end of the line.
+fn split_key(text: &str, line: usize) -> Result<Option<(String, &str)>, SceneError> {
+    if text.starts_with('[') || text.starts_with('{') {
+        return Ok(None);
+    }
+
+    let mut quote = None;
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    for (n, &(i, c)) in chars.iter().enumerate() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ':' && chars.get(n + 1).is_none_or(|(_, next)| next.is_whitespace()) => {
+                let key = parse_flow(text[..i].trim(), line)?;
+                return match key.as_str() {
+                    Some(key) => Ok(Some((key.to_string(), text[i + 1..].trim()))),
+                    None => Err(SceneError::at(line, "keys have to be scalars")),
+                };
+            }
+            None => {}
+        }
+    }
+    Ok(None)
+}
+
+fn parse_flow(text: &str, line: usize) -> Result<Node, SceneError> {
+    let mut parser = FlowParser { chars: text.chars().collect(), pos: 0, line };
+    let node = parser.parse_value(false)?;
+    parser.skip_whitespace();
+    if parser.pos < parser.chars.len() {
+        return Err(parser.error("unexpected characters after value"));
+    }
+    Ok(node)
+}
+
+struct FlowParser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl FlowParser {
+    fn error(&self, message: &str) -> SceneError {
+        SceneError::at(self.line, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn parse_value(&mut self, in_flow: bool) -> Result<Node, SceneError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('[') => self.parse_sequence(),
+            Some('{') => self.parse_mapping(),
+            Some('"') | Some('\'') => self.parse_quoted(),
+            _ => {
+                let scalar = self.parse_plain(if in_flow { ",]}" } else { "" })?;
+                if scalar.is_empty() {
+                    return Err(self.error("expected a value"));
+                }
+                Ok(Node::new(Value::Scalar(scalar), self.line))
+            }
+        }
+    }
+
+    fn parse_sequence(&mut self) -> Result<Node, SceneError> {
+        self.pos += 1;
+        let mut items = vec![];
+        loop {
+            self.skip_whitespace();
+            if items.is_empty() && self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(Node::new(Value::Sequence(items), self.line));
+            }
+            items.push(self.parse_value(true)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(Node::new(Value::Sequence(items), self.line));
+                }
+                _ => return Err(self.error("expected ',' or ']' in sequence")),
+            }
+        }
+    }
+
+    fn parse_mapping(&mut self) -> Result<Node, SceneError> {
+        self.pos += 1;
+        let mut entries = vec![];
+        loop {
+            self.skip_whitespace();
+            if entries.is_empty() && self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(Node::new(Value::Mapping(entries), self.line));
+            }
+            let key = match self.peek() {
+                Some('"') | Some('\'') => self.parse_quoted()?.as_str().unwrap_or_default().to_string(),
+                _ => self.parse_plain(":,}")?,
+            };
+            self.skip_whitespace();
+            if self.peek() != Some(':') {
+                return Err(self.error("expected ':' after key in mapping"));
+            }
+            self.pos += 1;
+            entries.push((key, self.parse_value(true)?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(Node::new(Value::Mapping(entries), self.line));
+                }
+                _ => return Err(self.error("expected ',' or '}' in mapping")),
+            }
+        }
+    }
+
+    fn parse_quoted(&mut self) -> Result<Node, SceneError> {
+        let quote = self.chars[self.pos];
+        self.pos += 1;
+        let mut value = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                c if c == quote => return Ok(Node::new(Value::Scalar(value), self.line)),
+                '\\' if quote == '"' => {
+                    let escaped = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    value.push(if escaped == 'n' { '\n' } else { escaped });
+                }
+                c => value.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    // Reads up to the end of the text or the first of the `terminators`.
+    fn parse_plain(&mut self, terminators: &str) -> Result<String, SceneError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| !terminators.contains(c)) {
+            self.pos += 1;
+        }
+        Ok(self.chars[start..self.pos].iter().collect::<String>().trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scalar(value: &str, line: usize) -> Node {
+        Node::new(Value::Scalar(value.to_string()), line)
+    }
+
+    #[test]
+    fn parse_yaml_reads_flow_values() {
+        // Given
+        let source = "a: 1\nb: [ 1, -2.5, [ x, y ] ]\nc: { d: 1, e: 'two words' }\nf: \"quoted: # not a comment\"";
+
+        // When
+        let node = parse_yaml(source).unwrap();
+
+        // Then
+        assert_eq!(node.get("a"), Some(&scalar("1", 1)));
+        assert_eq!(node.get("b"), Some(&Node::new(Value::Sequence(vec![
+            scalar("1", 2),
+            scalar("-2.5", 2),
+            Node::new(Value::Sequence(vec![scalar("x", 2), scalar("y", 2)]), 2),
+        ]), 2)));
+        assert_eq!(node.get("c").unwrap().get("e"), Some(&scalar("two words", 3)));
+        assert_eq!(node.get("f"), Some(&scalar("quoted: # not a comment", 4)));
+    }
+
+    #[test]
+    fn parse_yaml_reads_block_structures() {
+        // Given
+        let source = "\
+# a scene
+- add: camera
+  width: 100  # pixels
+
+- add: sphere
+  transform:
+    - [ scale, 1, 1, 1 ]
+  material:
+    color: [ 1, 0, 0 ]
+- define: list
+  value:
+  - one
+  -
+    - two
+";
+
+        // When
+        let node = parse_yaml(source).unwrap();
+
+        // Then
+        let items = node.as_sequence().unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].line(), 2);
+        assert_eq!(items[0].get("width"), Some(&scalar("100", 3)));
+        assert_eq!(items[1].get("transform").unwrap().as_sequence().unwrap()[0].line(), 7);
+        assert_eq!(items[1].get("material").unwrap().get("color").unwrap().line(), 9);
+        let list = items[2].get("value").unwrap().as_sequence().unwrap();
+        assert_eq!(list[0], scalar("one", 12));
+        assert_eq!(list[1].as_sequence().unwrap()[0], scalar("two", 14));
+    }
+
+    #[test]
+    fn parse_yaml_reads_empty_values() {
+        // Given
+        let source = "a:\nb: 1\n";
+
+        // When
+        let node = parse_yaml(source).unwrap();
+
+        // Then
+        assert_eq!(node.get("a"), Some(&Node::new(Value::Null, 1)));
+        assert_eq!(parse_yaml("# only a comment\n").unwrap().value(), &Value::Null);
+    }
+
+    #[test]
+    fn parse_yaml_reports_line_of_error() {
+        // Given
+        let cases = [
+            ("a: 1\nb: [ 1, 2\n", 2),
+            ("a: 1\n  b: 2\n", 2),
+            ("a: 1\nno colon here\n", 2),
+            ("a: 1\na: 2\n", 2),
+            ("- a: 1\n- b: \"open\n", 2),
+            ("a: 1\n\tb: 2\n", 2),
+            ("a: [ 1, , 2 ]\n", 1),
+        ];
+
+        for (source, line) in cases {
+            // When
+            let result = parse_yaml(source);
+
+            // Then
+            assert_eq!(result.unwrap_err().line(), Some(line), "{:?}", source);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::matrix::Matrix;
+use super::{matrix::Matrix, tuple::Tuple};
 
 pub fn translation(x: f64, y: f64, z: f64) -> Matrix {
     let mut out = Matrix::identity(4, 4);
@@ -61,10 +61,23 @@ pub fn shearing(xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Matrix
     out
 }
 
+pub fn view_transform(from: &Tuple, to: &Tuple, up: &Tuple) -> Matrix {
+    let forward = (to - from).normalize();
+    let left = forward.cross(&up.normalize());
+    // recompute up so that it is exactly perpendicular to forward and left
+    let true_up = left.cross(&forward);
+
+    let orientation = Matrix::from_values(4, 4, vec![
+        left.x(), left.y(), left.z(), 0.0,
+        true_up.x(), true_up.y(), true_up.z(), 0.0,
+        -forward.x(), -forward.y(), -forward.z(), 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    ]);
+    orientation * &translation(-from.x(), -from.y(), -from.z())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::raytracer::tuple::Tuple;
-
     use super::*;
 
     #[test]
@@ -334,4 +347,65 @@ mod tests {
         // Then
         assert_eq!(p2, Tuple::point(15.0, 0.0, 7.0));
     }
+
+    #[test]
+    fn view_transform_default_orientation() {
+        // Given
+        let from = Tuple::point(0.0, 0.0, 0.0);
+        let to = Tuple::point(0.0, 0.0, -1.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+
+        // When
+        let t = view_transform(&from, &to, &up);
+
+        // Then
+        assert_eq!(t, Matrix::identity(4, 4));
+    }
+
+    #[test]
+    fn view_transform_looking_in_positive_z_direction() {
+        // Given
+        let from = Tuple::point(0.0, 0.0, 0.0);
+        let to = Tuple::point(0.0, 0.0, 1.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+
+        // When
+        let t = view_transform(&from, &to, &up);
+
+        // Then
+        assert_eq!(t, scaling(-1.0, 1.0, -1.0));
+    }
+
+    #[test]
+    fn view_transform_moves_the_world() {
+        // Given
+        let from = Tuple::point(0.0, 0.0, 8.0);
+        let to = Tuple::point(0.0, 0.0, 0.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+
+        // When
+        let t = view_transform(&from, &to, &up);
+
+        // Then
+        assert_eq!(t, translation(0.0, 0.0, -8.0));
+    }
+
+    #[test]
+    fn view_transform_arbitrary() {
+        // Given
+        let from = Tuple::point(1.0, 3.0, 2.0);
+        let to = Tuple::point(4.0, -2.0, 8.0);
+        let up = Tuple::vector(1.0, 1.0, 0.0);
+
+        // When
+        let t = view_transform(&from, &to, &up);
+
+        // Then
+        assert_eq!(t, Matrix::from_values(4, 4, vec![
+            -0.50709, 0.50709, 0.67612, -2.36643,
+            0.76772, 0.60609, 0.12122, -2.82843,
+            -0.35857, 0.59761, -0.71714, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        ]));
+    }
 }
@@ -0,0 +1,658 @@
+use super::{color::Color, objects::{computations::{prepare_computations, Computations}, group::Group, intersection::Intersection, lights::Light, object::Object}, random::Random, ray::Ray, tuple::Tuple};
+
+/// How often a ray may bounce off reflective surfaces before the recursion stops.
+pub const DEFAULT_MAX_DEPTH: usize = 5;
+
+pub struct World {
+    objects: Vec<Box<dyn Object>>,
+    lights: Vec<Box<dyn Light>>,
+    max_depth: usize,
+}
+
+impl World {
+    pub fn new(objects: Vec<Box<dyn Object>>, lights: Vec<Box<dyn Light>>) -> World {
+        Self { objects, lights, max_depth: DEFAULT_MAX_DEPTH }
+    }
+
+    pub fn objects(&self) -> &Vec<Box<dyn Object>> {
+        &self.objects
+    }
+
+    pub fn add_object(&mut self, object: Box<dyn Object>) {
+        self.objects.push(object);
+    }
+
+    /// Moves all objects into a single group and splits it into a bounding volume hierarchy,
+    /// see `Object::divide`.
+    pub fn divide(&mut self, threshold: usize) {
+        let mut group = Group::new(std::mem::take(&mut self.objects));
+        group.divide(threshold);
+        self.objects = vec![Box::new(group)];
+    }
+
+    pub fn lights(&self) -> &Vec<Box<dyn Light>> {
+        &self.lights
+    }
+
+    pub fn add_light(&mut self, light: Box<dyn Light>) {
+        self.lights.push(light);
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    pub fn intersect_world(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let mut xs: Vec<Intersection> = self.objects.iter()
+            .flat_map(|o| o.intersects(ray))
+            .collect();
+        xs.sort_by(|a, b| a.t().total_cmp(&b.t()));
+        xs
+    }
+
+    pub fn shade_hit(&self, comps: &Computations, remaining: usize) -> Color {
+        let material = comps.object().material();
+
+        // jittered lights are seeded from the point being shaded, so a render doesn't depend on
+        // which thread shades the point or how often the program ran before
+        let point = comps.over_point();
+        let mut rng = Random::new(&[point.x().to_bits(), point.y().to_bits(), point.z().to_bits()]);
+
+        // every light contributes its own shading, so the results are simply summed up
+        let surface = self.lights.iter()
+            .fold(Color::default(), |acc, light| {
+                let illumination = light.illuminate(point, self, &mut rng);
+                acc + &material.lighting(comps.object(), &illumination, point, comps.eyev(), comps.normalv())
+            });
+
+        let reflected = self.reflected_color(comps, remaining);
+        let refracted = self.refracted_color(comps, remaining);
+
+        // surfaces that are both reflective and transparent blend the two by the Fresnel effect
+        if material.reflective() > 0.0 && material.transparency() > 0.0 {
+            let reflectance = comps.schlick();
+            return surface + &(reflected * reflectance) + &(refracted * (1.0 - reflectance));
+        }
+
+        surface + &reflected + &refracted
+    }
+
+    pub fn color_at(&self, ray: &Ray) -> Color {
+        self.color_at_depth(ray, self.max_depth)
+    }
+
+    pub fn color_at_depth(&self, ray: &Ray, remaining: usize) -> Color {
+        let xs = self.intersect_world(ray);
+        match Intersection::from_hit(&xs) {
+            Some(hit) => self.shade_hit(&prepare_computations(hit, ray, &xs), remaining),
+            None => Color::default(),
+        }
+    }
+
+    pub fn reflected_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let reflective = comps.object().material().reflective();
+
+        // stop bouncing once the depth is used up, otherwise two facing mirrors recurse forever
+        if reflective == 0.0 || remaining == 0 {
+            return Color::default();
+        }
+
+        let reflect_ray = Ray::new(comps.over_point().clone(), comps.reflectv().clone());
+        self.color_at_depth(&reflect_ray, remaining - 1) * reflective
+    }
+
+    pub fn refracted_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let transparency = comps.object().material().transparency();
+        if transparency == 0.0 || remaining == 0 {
+            return Color::default();
+        }
+
+        // Snell's law: sin(theta_i) / sin(theta_t) = n2 / n1
+        let n_ratio = comps.n1() / comps.n2();
+        let cos_i = comps.eyev().dot(comps.normalv());
+        let sin2_t = n_ratio.powi(2) * (1.0 - cos_i.powi(2));
+
+        // total internal reflection, no light passes through the surface
+        if sin2_t > 1.0 {
+            return Color::default();
+        }
+
+        let cos_t = (1.0 - sin2_t).sqrt();
+        let direction = comps.normalv() * (n_ratio * cos_i - cos_t) - &(comps.eyev() * n_ratio);
+        let refract_ray = Ray::new(comps.under_point().clone(), direction);
+
+        self.color_at_depth(&refract_ray, remaining - 1) * transparency
+    }
+
+    /// Whether an object lies between `point` and the point on a light at `light_position`.
+    pub fn is_shadowed(&self, light_position: &Tuple, point: &Tuple) -> bool {
+        let v = light_position - point;
+        self.is_blocked(point, &v.normalize(), v.magnitude())
+    }
+
+    /// Whether an object lies within `distance` of `point` in the normalized `direction`.
+    pub fn is_blocked(&self, point: &Tuple, direction: &Tuple, distance: f64) -> bool {
+        let r = Ray::new(point.clone(), direction.clone());
+        let xs = self.intersect_world(&r);
+
+        // only objects between the point and the light cast a shadow
+        match Intersection::from_hit(&xs) {
+            Some(hit) => hit.t() < distance,
+            None => false,
+        }
+    }
+}
+
+impl Default for World {
+    fn default() -> World {
+        Self::new(vec![], vec![])
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{f64::consts::FRAC_1_SQRT_2, sync::{atomic::{AtomicUsize, Ordering}, Arc}};
+
+    use crate::raytracer::{objects::{computations::tests::glass_sphere, lights::{LightSample, PointLight}, materials::Material, patterns::tests::TestPattern, plane::Plane, sphere::Sphere}, transformation, tuple::Tuple};
+
+    use super::*;
+
+    pub(crate) fn default_world() -> World {
+        let light = PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+
+        let mut s1 = Sphere::default();
+        s1.set_material(Material::new(Color::new(0.8, 1.0, 0.6), 0.1, 0.7, 0.2, 200.0));
+
+        let mut s2 = Sphere::default();
+        s2.set_transform(transformation::scaling(0.5, 0.5, 0.5));
+
+        World::new(vec![Box::new(s1), Box::new(s2)], vec![Box::new(light)])
+    }
+
+    #[test]
+    fn default_is_empty() {
+        // When
+        let w = World::default();
+
+        // Then
+        assert!(w.objects().is_empty());
+        assert!(w.lights().is_empty());
+    }
+
+    #[test]
+    fn add_object_and_light_append_members() {
+        // Given
+        let mut w = World::default();
+        let light = PointLight::new(Tuple::point(0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0));
+
+        // When
+        w.add_object(Box::new(Sphere::default()));
+        w.add_light(Box::new(light));
+
+        // Then
+        assert_eq!(w.objects().len(), 1);
+        assert_eq!(w.lights().len(), 1);
+    }
+
+    #[test]
+    fn intersect_world_returns_sorted_intersections() {
+        // Given
+        let w = default_world();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let xs = w.intersect_world(&r);
+
+        // Then
+        assert_eq!(xs.len(), 4);
+        assert_eq!(xs[0].t(), 4.0);
+        assert_eq!(xs[1].t(), 4.5);
+        assert_eq!(xs[2].t(), 5.5);
+        assert_eq!(xs[3].t(), 6.0);
+    }
+
+    #[test]
+    fn color_at_ray_misses() {
+        // Given
+        let w = default_world();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+
+        // When
+        let c = w.color_at(&r);
+
+        // Then
+        assert_eq!(c, Color::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn color_at_ray_hits() {
+        // Given
+        let w = default_world();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let c = w.color_at(&r);
+
+        // Then
+        assert_eq!(c, Color::new(0.38066, 0.47583, 0.2855));
+    }
+
+    #[test]
+    fn color_at_sums_all_lights() {
+        // Given
+        let mut w = default_world();
+        w.add_light(Box::new(PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0))));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let c = w.color_at(&r);
+
+        // Then
+        assert_eq!(c, Color::new(0.76132, 0.95166, 0.5710));
+    }
+
+    #[test]
+    fn is_shadowed_nothing_collinear_with_point_and_light() {
+        // Given
+        let w = default_world();
+        let light_position = Tuple::point(-10.0, 10.0, -10.0);
+        let p = Tuple::point(0.0, 10.0, 0.0);
+
+        // When & Then
+        assert!(!w.is_shadowed(&light_position, &p));
+    }
+
+    #[test]
+    fn is_shadowed_object_between_point_and_light() {
+        // Given
+        let w = default_world();
+        let light_position = Tuple::point(-10.0, 10.0, -10.0);
+        let p = Tuple::point(10.0, -10.0, 10.0);
+
+        // When & Then
+        assert!(w.is_shadowed(&light_position, &p));
+    }
+
+    #[test]
+    fn is_shadowed_object_behind_light() {
+        // Given
+        let w = default_world();
+        let light_position = Tuple::point(-10.0, 10.0, -10.0);
+        let p = Tuple::point(-20.0, 20.0, -20.0);
+
+        // When & Then
+        assert!(!w.is_shadowed(&light_position, &p));
+    }
+
+    #[test]
+    fn is_shadowed_object_behind_point() {
+        // Given
+        let w = default_world();
+        let light_position = Tuple::point(-10.0, 10.0, -10.0);
+        let p = Tuple::point(-2.0, 2.0, -2.0);
+
+        // When & Then
+        assert!(!w.is_shadowed(&light_position, &p));
+    }
+
+    #[test]
+    fn color_at_point_in_shadow_is_ambient() {
+        // Given
+        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let s1 = Sphere::default();
+        let mut s2 = Sphere::default();
+        s2.set_transform(transformation::translation(0.0, 0.0, 10.0));
+        let w = World::new(vec![Box::new(s1), Box::new(s2)], vec![Box::new(light)]);
+        let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let c = w.color_at(&r);
+
+        // Then
+        assert_eq!(c, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn color_at_offsets_point_to_avoid_acne() {
+        // Given
+        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let mut s = Sphere::default();
+        s.set_transform(transformation::translation(0.0, 0.0, 1.0));
+        let w = World::new(vec![Box::new(s)], vec![Box::new(light)]);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        let c = w.color_at(&r);
+
+        // Then
+        assert_eq!(c, Color::new(1.9, 1.9, 1.9));
+    }
+
+    #[test]
+    fn shade_hit_intersection() {
+        // Given
+        let w = default_world();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, w.objects()[0].as_ref());
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+        let c = w.shade_hit(&comps, DEFAULT_MAX_DEPTH);
+
+        // Then
+        assert_eq!(c, Color::new(0.38066, 0.47583, 0.2855));
+    }
+
+    // A point light that counts how often it is asked for samples.
+    struct CountingLight {
+        light: PointLight,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Light for CountingLight {
+        fn intensity(&self) -> &Color {
+            self.light.intensity()
+        }
+
+        fn samples(&self, point: &Tuple, rng: &mut Random) -> Vec<LightSample> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            self.light.samples(point, rng)
+        }
+    }
+
+    #[test]
+    fn shade_hit_samples_each_light_once() {
+        // Given
+        let mut w = default_world();
+        let light = PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let calls = Arc::new(AtomicUsize::new(0));
+        w.lights = vec![Box::new(CountingLight { light, calls: Arc::clone(&calls) })];
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, w.objects()[0].as_ref());
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+        let c = w.shade_hit(&comps, DEFAULT_MAX_DEPTH);
+
+        // Then
+        assert_eq!(c, Color::new(0.38066, 0.47583, 0.2855));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn shade_hit_intersection_from_inside() {
+        // Given
+        let mut w = default_world();
+        w.lights = vec![Box::new(PointLight::new(Tuple::point(0.0, 0.25, 0.0), Color::new(1.0, 1.0, 1.0)))];
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(0.5, w.objects()[1].as_ref());
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+        let c = w.shade_hit(&comps, DEFAULT_MAX_DEPTH);
+
+        // Then
+        assert_eq!(c, Color::new(0.90498, 0.90498, 0.90498));
+    }
+
+    fn reflective_plane(reflective: f64) -> Plane {
+        let mut p = Plane::default();
+        let mut m = Material::default();
+        m.set_reflective(reflective);
+        p.set_material(m);
+        p.set_transform(transformation::translation(0.0, -1.0, 0.0));
+        p
+    }
+
+    #[test]
+    fn reflected_color_for_nonreflective_material() {
+        // Given
+        let mut w = default_world();
+        let mut m = w.objects()[1].material().clone();
+        m.set_ambient(1.0);
+        w.objects[1].set_material(m);
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(1.0, w.objects()[1].as_ref());
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+        let c = w.reflected_color(&comps, DEFAULT_MAX_DEPTH);
+
+        // Then
+        assert_eq!(c, Color::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn reflected_color_for_reflective_material() {
+        // Given
+        let mut w = default_world();
+        w.add_object(Box::new(reflective_plane(0.5)));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let i = Intersection::new(2_f64.sqrt(), w.objects()[2].as_ref());
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+        let c = w.reflected_color(&comps, DEFAULT_MAX_DEPTH);
+
+        // Then
+        assert_eq!(c, Color::new(0.19033, 0.23791, 0.14274));
+    }
+
+    #[test]
+    fn shade_hit_with_reflective_material() {
+        // Given
+        let mut w = default_world();
+        w.add_object(Box::new(reflective_plane(0.5)));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let i = Intersection::new(2_f64.sqrt(), w.objects()[2].as_ref());
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+        let c = w.shade_hit(&comps, DEFAULT_MAX_DEPTH);
+
+        // Then
+        assert_eq!(c, Color::new(0.87676, 0.92434, 0.82917));
+    }
+
+    #[test]
+    fn color_at_with_mutually_reflective_surfaces_terminates() {
+        // Given
+        let light = PointLight::new(Tuple::point(0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0));
+        let lower = reflective_plane(1.0);
+        let mut upper = reflective_plane(1.0);
+        upper.set_transform(transformation::translation(0.0, 1.0, 0.0));
+        let w = World::new(vec![Box::new(lower), Box::new(upper)], vec![Box::new(light)]);
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+
+        // When & Then
+        w.color_at(&r);
+    }
+
+    #[test]
+    fn reflected_color_at_maximum_recursive_depth() {
+        // Given
+        let mut w = default_world();
+        w.add_object(Box::new(reflective_plane(0.5)));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let i = Intersection::new(2_f64.sqrt(), w.objects()[2].as_ref());
+
+        // When
+        let comps = prepare_computations(&i, &r, &[]);
+        let c = w.reflected_color(&comps, 0);
+
+        // Then
+        assert_eq!(c, Color::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn new_uses_default_max_depth() {
+        // Given
+        let mut w = World::default();
+
+        // When
+        w.set_max_depth(2);
+
+        // Then
+        assert_eq!(World::default().max_depth(), DEFAULT_MAX_DEPTH);
+        assert_eq!(w.max_depth(), 2);
+    }
+
+    #[test]
+    fn refracted_color_with_opaque_surface() {
+        // Given
+        let w = default_world();
+        let shape = w.objects()[0].as_ref();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = vec![Intersection::new(4.0, shape), Intersection::new(6.0, shape)];
+
+        // When
+        let comps = prepare_computations(&xs[0], &r, &xs);
+        let c = w.refracted_color(&comps, 5);
+
+        // Then
+        assert_eq!(c, Color::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn refracted_color_at_maximum_recursive_depth() {
+        // Given
+        let mut w = default_world();
+        w.objects[0].set_material(glass_sphere().material().clone());
+        let shape = w.objects()[0].as_ref();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = vec![Intersection::new(4.0, shape), Intersection::new(6.0, shape)];
+
+        // When
+        let comps = prepare_computations(&xs[0], &r, &xs);
+        let c = w.refracted_color(&comps, 0);
+
+        // Then
+        assert_eq!(c, Color::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn refracted_color_under_total_internal_reflection() {
+        // Given
+        let mut w = default_world();
+        w.objects[0].set_material(glass_sphere().material().clone());
+        let shape = w.objects()[0].as_ref();
+        let r = Ray::new(Tuple::point(0.0, 0.0, FRAC_1_SQRT_2), Tuple::vector(0.0, 1.0, 0.0));
+        let xs = vec![Intersection::new(-FRAC_1_SQRT_2, shape), Intersection::new(FRAC_1_SQRT_2, shape)];
+
+        // When
+        // we're inside the sphere, so look at the second intersection
+        let comps = prepare_computations(&xs[1], &r, &xs);
+        let c = w.refracted_color(&comps, 5);
+
+        // Then
+        assert_eq!(c, Color::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn refracted_color_with_refracted_ray() {
+        // Given
+        let mut w = default_world();
+        let mut m = w.objects()[0].material().clone();
+        m.set_ambient(1.0);
+        m.set_pattern(Box::new(TestPattern::default()));
+        w.objects[0].set_material(m);
+        let mut m = w.objects()[1].material().clone();
+        m.set_transparency(1.0);
+        m.set_refractive_index(1.5);
+        w.objects[1].set_material(m);
+        let a = w.objects()[0].as_ref();
+        let b = w.objects()[1].as_ref();
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.1), Tuple::vector(0.0, 1.0, 0.0));
+        let xs = vec![
+            Intersection::new(-0.9899, a),
+            Intersection::new(-0.4899, b),
+            Intersection::new(0.4899, b),
+            Intersection::new(0.9899, a),
+        ];
+
+        // When
+        let comps = prepare_computations(&xs[2], &r, &xs);
+        let c = w.refracted_color(&comps, 5);
+
+        // Then
+        assert_eq!(c, Color::new(0.0, 0.99887, 0.04722));
+    }
+
+    #[test]
+    fn shade_hit_with_transparent_material() {
+        // Given
+        let mut w = default_world();
+        let mut floor = Plane::default();
+        floor.set_transform(transformation::translation(0.0, -1.0, 0.0));
+        let mut m = Material::default();
+        m.set_transparency(0.5);
+        m.set_refractive_index(1.5);
+        floor.set_material(m);
+        w.add_object(Box::new(floor));
+        let mut ball = Sphere::default();
+        let mut m = Material::default();
+        m.set_color(Color::new(1.0, 0.0, 0.0));
+        m.set_ambient(0.5);
+        ball.set_material(m);
+        ball.set_transform(transformation::translation(0.0, -3.5, -0.5));
+        w.add_object(Box::new(ball));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let xs = vec![Intersection::new(2_f64.sqrt(), w.objects()[2].as_ref())];
+
+        // When
+        let comps = prepare_computations(&xs[0], &r, &xs);
+        let c = w.shade_hit(&comps, 5);
+
+        // Then
+        assert_eq!(c, Color::new(0.93642, 0.68642, 0.68642));
+    }
+
+    #[test]
+    fn shade_hit_with_reflective_transparent_material() {
+        // Given
+        let mut w = default_world();
+        let mut floor = Plane::default();
+        floor.set_transform(transformation::translation(0.0, -1.0, 0.0));
+        let mut m = Material::default();
+        m.set_reflective(0.5);
+        m.set_transparency(0.5);
+        m.set_refractive_index(1.5);
+        floor.set_material(m);
+        w.add_object(Box::new(floor));
+        let mut ball = Sphere::default();
+        let mut m = Material::default();
+        m.set_color(Color::new(1.0, 0.0, 0.0));
+        m.set_ambient(0.5);
+        ball.set_material(m);
+        ball.set_transform(transformation::translation(0.0, -3.5, -0.5));
+        w.add_object(Box::new(ball));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+        let xs = vec![Intersection::new(2_f64.sqrt(), w.objects()[2].as_ref())];
+
+        // When
+        let comps = prepare_computations(&xs[0], &r, &xs);
+        let c = w.shade_hit(&comps, 5);
+
+        // Then
+        assert_eq!(c, Color::new(0.93391, 0.69643, 0.69243));
+    }
+
+    #[test]
+    fn divide_keeps_rendered_colors() {
+        // Given
+        let mut w = default_world();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        // When
+        w.divide(1);
+
+        // Then
+        assert_eq!(w.objects().len(), 1);
+        assert_eq!(w.intersect_world(&r).len(), 4);
+        assert_eq!(w.color_at(&r), Color::new(0.38066, 0.47583, 0.2855));
+    }
+}